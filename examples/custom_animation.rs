@@ -25,13 +25,13 @@ impl Animation for PulsingCircle {
     }
 
     fn render(&self, grid: &mut BrailleGrid) {
-        let center_x = grid.dot_width() / 2;
-        let center_y = grid.dot_height() / 2;
+        let center_x = grid.dot_width() as i32 / 2;
+        let center_y = grid.dot_height() as i32 / 2;
 
         // Calculate pulsing radius
         let progress = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
         let pulse = (progress * std::f32::consts::PI * 4.0).sin().abs();
-        let radius = (10.0 + pulse * 15.0) as usize;
+        let radius = (10.0 + pulse * 15.0) as i32;
 
         // Draw pulsing circle
         grid.draw_circle(
@@ -46,7 +46,7 @@ impl Animation for PulsingCircle {
         );
 
         // Draw center dot
-        grid.set_dot_with_color(center_x, center_y, Color::WHITE);
+        grid.plot(center_x, center_y, Color::WHITE);
     }
 
     fn name(&self) -> &str {
@@ -0,0 +1,123 @@
+// Explosion animation for failed commands
+
+use super::Animation;
+use crate::braille::{BrailleGrid, Color};
+use std::time::Duration;
+
+/// Debris particle flying out from the blast
+struct Debris {
+    /// Direction of travel (unit vector)
+    dir_x: f32,
+    dir_y: f32,
+    /// Fraction of the blast radius this particle reaches
+    reach: f32,
+}
+
+/// Flash, shockwave and flying debris that fades into smoke
+pub struct ExplosionAnimation {
+    elapsed: Duration,
+    duration: Duration,
+    debris: Vec<Debris>,
+}
+
+impl ExplosionAnimation {
+    pub fn new(duration: Duration) -> Self {
+        // Spread debris evenly using the golden angle, with varied reach
+        let debris = (0..80)
+            .map(|i| {
+                let angle = i as f32 * 2.399_963;
+                Debris {
+                    dir_x: angle.cos(),
+                    dir_y: angle.sin(),
+                    reach: 0.35 + ((i * 37) % 65) as f32 / 100.0,
+                }
+            })
+            .collect();
+
+        Self {
+            elapsed: Duration::ZERO,
+            duration,
+            debris,
+        }
+    }
+
+    /// Color of the debris as it cools down
+    fn debris_color(progress: f32) -> Color {
+        if progress < 0.2 {
+            Color::new(255, 255, 180) // White-hot
+        } else if progress < 0.4 {
+            Color::new(255, 220, 0) // Yellow
+        } else if progress < 0.6 {
+            Color::new(255, 120, 0) // Orange
+        } else if progress < 0.8 {
+            Color::new(220, 40, 0) // Red
+        } else {
+            Color::new(110, 110, 110) // Smoke
+        }
+    }
+}
+
+impl Default for ExplosionAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(1500))
+    }
+}
+
+impl Animation for ExplosionAnimation {
+    fn update(&mut self, delta_time: Duration) -> bool {
+        self.elapsed += delta_time;
+        self.elapsed < self.duration
+    }
+
+    fn render(&self, grid: &mut BrailleGrid) {
+        let width = grid.dot_width();
+        let height = grid.dot_height();
+        let center_x = width / 2;
+        let center_y = height / 2;
+        let max_radius = (width.min(height * 2) / 2) as f32;
+
+        let progress = (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0);
+        let eased = 1.0 - (1.0 - progress).powi(2); // Fast out, slow settle
+
+        // Initial flash: a filled white-hot core
+        if progress < 0.15 {
+            let core = (max_radius * 0.4 * progress / 0.15) as i32;
+            for r in 1..=core {
+                grid.draw_circle(center_x as i32, center_y as i32, r, Color::new(255, 255, 200));
+            }
+        }
+
+        // Shockwave ring
+        if progress < 0.5 {
+            let radius = (max_radius * eased * 1.2) as i32;
+            grid.draw_circle(center_x as i32, center_y as i32, radius, Color::new(255, 160, 0));
+        }
+
+        // Debris, drifting down a little as it slows
+        let color = Self::debris_color(progress);
+        for debris in &self.debris {
+            let distance = max_radius * debris.reach * eased;
+            let x = center_x as f32 + debris.dir_x * distance;
+            let y = center_y as f32 + debris.dir_y * distance * 0.5 + progress * progress * 8.0;
+            if x >= 0.0 && y >= 0.0 {
+                grid.set_dot_with_color(x as usize, y as usize, color);
+                // Trail behind fast particles early on
+                if progress < 0.4 {
+                    let tx = x - debris.dir_x * 2.0;
+                    let ty = y - debris.dir_y;
+                    if tx >= 0.0 && ty >= 0.0 {
+                        grid.set_dot_with_color(tx as usize, ty as usize, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Explosion"
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+}
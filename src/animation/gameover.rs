@@ -0,0 +1,92 @@
+// Arcade GAME OVER screen for failed commands
+
+use super::text::{dmd_font, draw_text_centered, fit_scale};
+use super::Animation;
+use crate::braille::{BrailleGrid, Color};
+use std::time::Duration;
+
+/// "GAME OVER" typed out letter by letter, then blinking
+pub struct GameOverAnimation {
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl GameOverAnimation {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+
+    /// Number of letters shown so far (of the 8 in GAME OVER)
+    fn letters_shown(&self) -> usize {
+        let progress = self.elapsed.as_secs_f32() / (self.duration.as_secs_f32() * 0.5);
+        ((progress * 8.0) as usize + 1).min(8)
+    }
+}
+
+impl Default for GameOverAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(2))
+    }
+}
+
+impl Animation for GameOverAnimation {
+    fn update(&mut self, delta_time: Duration) -> bool {
+        self.elapsed += delta_time;
+        self.elapsed < self.duration
+    }
+
+    fn render(&self, grid: &mut BrailleGrid) {
+        let width = grid.dot_width();
+        let height = grid.dot_height();
+        let shown = self.letters_shown();
+
+        // Once fully typed, blink like an idle arcade cabinet
+        if shown == 8 {
+            let since = self.elapsed.as_secs_f32() - self.duration.as_secs_f32() * 0.5;
+            if since > 0.0 && (since / 0.3) as usize % 2 == 1 {
+                return;
+            }
+        }
+
+        let color = Color::new(255, 50, 50);
+        let center_x = (width / 2) as i32;
+        let (game, over) = ("GAME", "OVER");
+
+        // Two lines when there's room, otherwise one
+        let two_line_scale = fit_scale(game, width * 4 / 5, height * 2 / 5);
+        let one_line_scale = fit_scale("GAME OVER", width * 9 / 10, height * 3 / 4);
+        if two_line_scale > one_line_scale {
+            let scale = two_line_scale;
+            let line_height = (dmd_font().line_height() + 2) * scale;
+            let top = (height.saturating_sub(line_height * 2) / 2) as i32;
+            let first = &game[..shown.min(4)];
+            let second = &over[..shown.saturating_sub(4)];
+            // Keep letters in place while typing by padding to full width
+            draw_text_centered(grid, &format!("{:<4}", first), center_x, top, scale, color);
+            draw_text_centered(
+                grid,
+                &format!("{:<4}", second),
+                center_x,
+                top + line_height as i32,
+                scale,
+                color,
+            );
+        } else {
+            let scale = one_line_scale;
+            let top = (height.saturating_sub(dmd_font().line_height() * scale) / 2) as i32;
+            let text: String = "GAME OVER".chars().take(shown + usize::from(shown > 4)).collect();
+            draw_text_centered(grid, &format!("{:<9}", text), center_x, top, scale, color);
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Game Over"
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+}
@@ -9,9 +9,6 @@ use std::time::Duration;
 
 /// Matrix rain that decodes output text
 pub struct MatrixRainAnimation {
-    /// Lines of text to decode (the real output)
-    output_lines: Vec<String>,
-
     /// Rain columns (one per character position)
     columns: Vec<RainColumn>,
 
@@ -24,11 +21,7 @@ pub struct MatrixRainAnimation {
     /// Decode threshold (0.0-1.0) - when to reveal real char
     decode_threshold: f32,
 
-    /// Speed variation factor (0.0-1.0) - higher = more chaos
-    noise_factor: f32,
-
-    /// Grid dimensions
-    width: usize,
+    /// Number of output lines decoded
     height: usize,
 
     /// All columns finished?
@@ -60,6 +53,12 @@ struct RainColumn {
 }
 
 impl MatrixRainAnimation {
+    /// Most lines decoded; more than a screenful can't be seen anyway
+    pub const MAX_LINES: usize = 200;
+
+    /// Most characters decoded per line
+    pub const MAX_WIDTH: usize = 400;
+
     /// Matrix character pool (Katakana + symbols)
     const MATRIX_CHARS: &'static [char] = &[
         'ﾊ', 'ﾐ', 'ﾋ', 'ｰ', 'ｳ', 'ｼ', 'ﾅ', 'ﾓ', 'ﾆ', 'ｻ', 'ﾜ', 'ﾂ', 'ｵ', 'ﾘ', 'ｱ',
@@ -80,10 +79,13 @@ impl MatrixRainAnimation {
         decode_threshold: f32,
         noise_factor: f32,
     ) -> Self {
-        let output_lines: Vec<String> = output.lines().map(|s| s.to_string()).collect();
+        // Huge output would mean millions of columns; only decode what fits
+        let output_lines: Vec<String> = output
+            .lines()
+            .take(Self::MAX_LINES)
+            .map(|s| s.chars().take(Self::MAX_WIDTH).collect())
+            .collect();
 
-        // Calculate grid size needed
-        let width = output_lines.iter().map(|s| s.len()).max().unwrap_or(80);
         let height = output_lines.len();
 
         // Create a column for each character position
@@ -112,13 +114,10 @@ impl MatrixRainAnimation {
         }
 
         Self {
-            output_lines,
             columns,
             elapsed: Duration::ZERO,
             duration,
             decode_threshold,
-            noise_factor,
-            width,
             height,
             all_decoded: false,
         }
@@ -286,6 +285,10 @@ impl Animation for MatrixRainAnimation {
     fn name(&self) -> &str {
         "Matrix Rain"
     }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
 }
 
 impl Default for MatrixRainAnimation {
@@ -303,8 +306,8 @@ mod tests {
         let output = "On branch main\nYour branch is up to date".to_string();
         let anim = MatrixRainAnimation::new(output);
 
-        assert_eq!(anim.output_lines.len(), 2);
-        assert!(anim.columns.len() > 0);
+        assert_eq!(anim.height, 2);
+        assert!(!anim.columns.is_empty());
     }
 
     #[test]
@@ -328,4 +331,18 @@ mod tests {
         let decoded = anim.columns.iter().filter(|c| c.decoded).count();
         assert!(decoded > 0);
     }
+
+    #[test]
+    fn test_matrix_rain_bounds_huge_output() {
+        let line = "x".repeat(10_000);
+        let output = vec![line.as_str(); 5_000].join("\n");
+        let anim = MatrixRainAnimation::new(output);
+
+        assert_eq!(anim.height, MatrixRainAnimation::MAX_LINES);
+        assert!(anim.columns.iter().all(|c| c.x < MatrixRainAnimation::MAX_WIDTH));
+        assert_eq!(
+            anim.columns.len(),
+            MatrixRainAnimation::MAX_LINES * MatrixRainAnimation::MAX_WIDTH
+        );
+    }
 }
@@ -12,6 +12,13 @@ mod confetti;
 mod trophy;
 mod framebased;
 mod matrixrain;
+mod progress;
+mod explosion;
+mod redx;
+mod tilt;
+mod gameover;
+mod text;
+mod registry;
 
 pub use spinner::SpinnerAnimation;
 pub use rocket::RocketAnimation;
@@ -25,9 +32,19 @@ pub use confetti::ConfettiAnimation;
 pub use trophy::TrophyAnimation;
 pub use framebased::FrameBasedAnimation;
 pub use matrixrain::MatrixRainAnimation;
-
-use crate::braille::BrailleGrid;
-use crate::rendering::{RenderMode, TerminalRenderer};
+pub use progress::{ProgressAnimation, ProgressHandle};
+pub use explosion::ExplosionAnimation;
+pub use redx::RedXAnimation;
+pub use tilt::TiltAnimation;
+pub use gameover::GameOverAnimation;
+pub use registry::{AnimationFactory, AnimationInfo, AnimationParams, AnimationRegistry};
+
+use crate::braille::{BrailleGrid, CellEncoding, Color, ColorPolicy};
+use crate::rendering::{
+    ColorDepth, Frame, FrameBuffer, FrameSink, OutputPane, RenderMode, RenderStats,
+    TerminalRenderer,
+};
+use crate::signals::{self, Interrupted};
 use anyhow::Result;
 use std::time::{Duration, Instant};
 
@@ -49,9 +66,121 @@ pub trait Animation {
     }
 }
 
+impl<A: Animation + ?Sized> Animation for Box<A> {
+    fn update(&mut self, delta_time: Duration) -> bool {
+        (**self).update(delta_time)
+    }
+
+    fn render(&self, grid: &mut BrailleGrid) {
+        (**self).render(grid)
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        (**self).duration()
+    }
+}
+
+/// Draws another animation in a single color
+pub struct TintedAnimation<A> {
+    animation: A,
+    color: Color,
+}
+
+impl<A: Animation> TintedAnimation<A> {
+    /// Wrap `animation`, recoloring every dot it draws
+    pub fn new(animation: A, color: Color) -> Self {
+        Self { animation, color }
+    }
+}
+
+impl<A: Animation> Animation for TintedAnimation<A> {
+    fn update(&mut self, delta_time: Duration) -> bool {
+        self.animation.update(delta_time)
+    }
+
+    fn render(&self, grid: &mut BrailleGrid) {
+        self.animation.render(grid);
+        grid.tint(self.color);
+    }
+
+    fn name(&self) -> &str {
+        self.animation.name()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.animation.duration()
+    }
+}
+
+/// Frame rate animations are played at
+const TARGET_FPS: u32 = 60;
+
+/// Time source for a playback loop
+///
+/// Real time, with frames paced to [`TARGET_FPS`], or simulated time that
+/// advances exactly one frame per tick without sleeping (headless mode).
+struct FrameClock {
+    simulated: bool,
+    started: Instant,
+    last_frame: Instant,
+    /// Frames started, for simulated time
+    ticks: u32,
+}
+
+impl FrameClock {
+    const FRAME: Duration = Duration::from_nanos(1_000_000_000 / TARGET_FPS as u64);
+
+    fn new(simulated: bool) -> Self {
+        let now = Instant::now();
+        Self {
+            simulated,
+            started: now,
+            last_frame: now,
+            ticks: 0,
+        }
+    }
+
+    /// Start a frame, returning the time since the previous one
+    fn tick(&mut self) -> Duration {
+        if self.simulated {
+            // Exact frame boundaries, so 60 ticks make exactly one second
+            let before = self.elapsed();
+            self.ticks += 1;
+            return self.elapsed() - before;
+        }
+        let now = Instant::now();
+        let delta = now.duration_since(self.last_frame);
+        self.last_frame = now;
+        delta
+    }
+
+    /// Sleep for what's left of the frame (no-op for simulated time)
+    fn pace(&self) {
+        let spent = self.last_frame.elapsed();
+        if !self.simulated && spent < Self::FRAME {
+            std::thread::sleep(Self::FRAME - spent);
+        }
+    }
+
+    /// Time played so far
+    fn elapsed(&self) -> Duration {
+        if self.simulated {
+            Duration::from_secs(1) * self.ticks / TARGET_FPS
+        } else {
+            self.started.elapsed()
+        }
+    }
+}
+
 /// Animation player for running animations
 pub struct AnimationPlayer {
-    renderer: TerminalRenderer,
+    sink: Box<dyn FrameSink>,
+    /// How cell colors are picked from the dots animations draw
+    color_policy: ColorPolicy,
 }
 
 impl AnimationPlayer {
@@ -68,41 +197,121 @@ impl AnimationPlayer {
     /// Create an animation player with inline mode using optimal height
     /// Automatically uses 1/3 of terminal height (min 15, max 40 lines)
     pub fn inline_auto() -> Result<Self> {
+        crate::rendering::require_terminal()?;
         let (_, terminal_height) = crossterm::terminal::size()?;
 
         // Use 1/3 of terminal height, with reasonable bounds
-        let height = (terminal_height / 3).max(15).min(40);
+        let height = (terminal_height / 3).clamp(15, 40);
 
         Self::with_mode(RenderMode::Inline { height })
     }
 
+    /// Create an animation player that records frames instead of drawing them
+    ///
+    /// Time is simulated: every frame advances it by 1/60 s and nothing
+    /// sleeps, so playback is instant and repeatable. Conditions passed to
+    /// [`play_while`](Self::play_while) should therefore count frames rather
+    /// than watch the clock. Frames are read back with
+    /// [`frames`](Self::frames).
+    ///
+    /// # Example
+    /// ```
+    /// use crabcrust::{AnimationPlayer, RocketAnimation};
+    ///
+    /// let mut player = AnimationPlayer::headless(40, 12).unwrap();
+    /// player.play(RocketAnimation::new(std::time::Duration::from_secs(1))).unwrap();
+    /// assert_eq!(player.frames().len(), 60);
+    /// ```
+    pub fn headless(width: u16, height: u16) -> Result<Self> {
+        Self::with_mode(RenderMode::Headless { width, height })
+    }
+
     /// Create an animation player with a specific render mode
     pub fn with_mode(mode: RenderMode) -> Result<Self> {
-        Ok(Self {
-            renderer: TerminalRenderer::with_mode(mode)?,
+        Ok(match mode {
+            RenderMode::Headless { width, height } => Self::with_sink(FrameBuffer::new(width, height)),
+            _ => Self::with_sink(TerminalRenderer::with_mode(mode)?),
         })
     }
 
+    /// Create an animation player drawing into any frame sink
+    ///
+    /// # Example
+    /// ```no_run
+    /// use crabcrust::{AnimationPlayer, RenderMode, RenderTarget, TerminalRenderer};
+    ///
+    /// // Keep stdout clean for piped output
+    /// let renderer = TerminalRenderer::with_target(RenderMode::Inline { height: 10 }, RenderTarget::Stderr)?;
+    /// let mut player = AnimationPlayer::with_sink(renderer);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_sink(sink: impl FrameSink + 'static) -> Self {
+        Self {
+            sink: Box::new(sink),
+            color_policy: ColorPolicy::default(),
+        }
+    }
+
+    /// Draw colors at a different depth than detected
+    pub fn with_color_depth(mut self, color_depth: ColorDepth) -> Self {
+        self.sink.set_color_depth(color_depth);
+        self
+    }
+
+    /// Draw cells with other characters than Braille
+    pub fn with_cell_encoding(mut self, encoding: CellEncoding) -> Self {
+        self.sink.set_cell_encoding(encoding);
+        self
+    }
+
+    /// Pick cell colors from dot colors with `policy` (default: majority)
+    pub fn with_color_policy(mut self, policy: ColorPolicy) -> Self {
+        self.color_policy = policy;
+        self
+    }
+
+    /// Create the grid animations draw on
+    fn grid(&self, width: u16, height: u16) -> BrailleGrid {
+        BrailleGrid::new(width as usize, height as usize).with_color_policy(self.color_policy)
+    }
+
+    /// Get the frames recorded so far (recording sinks like headless mode only)
+    pub fn frames(&self) -> &[Frame] {
+        self.sink.frames()
+    }
+
+    /// Take the frames recorded so far, leaving none
+    pub fn take_frames(&mut self) -> Vec<Frame> {
+        self.sink.take_frames()
+    }
+
+    /// Get the bytes written for the frames drawn so far, if the sink
+    /// writes any
+    pub fn render_stats(&self) -> Option<RenderStats> {
+        self.sink.stats()
+    }
+
+    /// Start a clock for one playback
+    fn clock(&self) -> FrameClock {
+        FrameClock::new(!self.sink.is_realtime())
+    }
+
     /// Play an animation to completion
     pub fn play<A: Animation>(&mut self, mut animation: A) -> Result<()> {
-        let (width, height) = self.renderer.size()?;
-        let mut grid = BrailleGrid::new(width as usize, height as usize);
-
-        let mut last_frame = Instant::now();
-        let target_fps = 60;
-        let frame_duration = Duration::from_millis(1000 / target_fps);
+        let (width, height) = self.sink.size()?;
+        let mut grid = self.grid(width, height);
+        let mut clock = self.clock();
 
         loop {
-            let now = Instant::now();
-            let delta = now.duration_since(last_frame);
+            Self::check_interrupted()?;
 
             // Update animation
-            let should_continue = animation.update(delta);
+            let should_continue = animation.update(clock.tick());
 
             // Render
             grid.clear();
             animation.render(&mut grid);
-            self.renderer.render_braille(&grid)?;
+            self.sink.render(&grid)?;
 
             // Check if done
             if !should_continue {
@@ -110,58 +319,107 @@ impl AnimationPlayer {
             }
 
             // Frame rate limiting
-            let elapsed = now.elapsed();
-            if elapsed < frame_duration {
-                std::thread::sleep(frame_duration - elapsed);
-            }
-
-            last_frame = now;
+            clock.pace();
         }
 
         Ok(())
     }
 
     /// Play animation for a specific duration
-    pub fn play_for<A: Animation>(
-        &mut self,
-        mut animation: A,
-        duration: Duration,
-    ) -> Result<()> {
-        let (width, height) = self.renderer.size()?;
-        let mut grid = BrailleGrid::new(width as usize, height as usize);
+    pub fn play_for<A: Animation>(&mut self, animation: A, duration: Duration) -> Result<()> {
+        self.play_until(animation, |elapsed| elapsed < duration)
+    }
 
-        let start = Instant::now();
-        let mut last_frame = start;
-        let target_fps = 60;
-        let frame_duration = Duration::from_millis(1000 / target_fps);
+    /// Play animation for as long as `keep_playing` returns true
+    ///
+    /// The condition is checked once per frame, which makes this suitable for
+    /// animating while a background command runs.
+    pub fn play_while<A, F>(&mut self, animation: A, mut keep_playing: F) -> Result<()>
+    where
+        A: Animation,
+        F: FnMut() -> bool,
+    {
+        self.play_until(animation, |_| keep_playing())
+    }
+
+    /// Play animation while `keep_playing` returns true for the time played so far
+    fn play_until<A, F>(&mut self, mut animation: A, mut keep_playing: F) -> Result<()>
+    where
+        A: Animation,
+        F: FnMut(Duration) -> bool,
+    {
+        let (width, height) = self.sink.size()?;
+        let mut grid = self.grid(width, height);
+        let mut clock = self.clock();
 
-        while start.elapsed() < duration {
-            let now = Instant::now();
-            let delta = now.duration_since(last_frame);
+        while keep_playing(clock.elapsed()) {
+            Self::check_interrupted()?;
 
             // Update animation
-            animation.update(delta);
+            animation.update(clock.tick());
 
             // Render
             grid.clear();
             animation.render(&mut grid);
-            self.renderer.render_braille(&grid)?;
+            self.sink.render(&grid)?;
 
             // Frame rate limiting
-            let elapsed = now.elapsed();
-            if elapsed < frame_duration {
-                std::thread::sleep(frame_duration - elapsed);
-            }
+            clock.pace();
+        }
+
+        Ok(())
+    }
+
+    /// Play animation above a live output pane for as long as `poll` returns true
+    ///
+    /// `poll` is called once per frame to push new lines into the pane; the
+    /// animation gets whatever rows the pane leaves free.
+    pub fn play_with_output<A, F>(
+        &mut self,
+        mut animation: A,
+        pane: &mut OutputPane,
+        mut poll: F,
+    ) -> Result<()>
+    where
+        A: Animation,
+        F: FnMut(&mut OutputPane) -> bool,
+    {
+        let (width, height) = self.sink.size()?;
+        let grid_height = height.saturating_sub(pane.rows());
+        let mut grid = self.grid(width, grid_height);
+        let mut clock = self.clock();
+
+        while poll(pane) {
+            Self::check_interrupted()?;
+
+            // Update animation
+            animation.update(clock.tick());
+
+            // Render
+            grid.clear();
+            animation.render(&mut grid);
+            self.sink.render_with_pane(&grid, pane)?;
 
-            last_frame = now;
+            // Frame rate limiting
+            clock.pace();
         }
 
         Ok(())
     }
 
-    /// Get access to the terminal renderer
-    pub fn renderer_mut(&mut self) -> &mut TerminalRenderer {
-        &mut self.renderer
+    /// Stop playback if a termination signal arrived
+    ///
+    /// Returning [`Interrupted`] unwinds through the renderer's cleanup guard.
+    fn check_interrupted() -> Result<()> {
+        match signals::pending() {
+            Some(signal) => Err(Interrupted { signal }.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Get access to the frame sink
+    pub fn sink_mut(&mut self) -> &mut dyn FrameSink {
+        self.sink.as_mut()
     }
 }
 
@@ -170,3 +428,62 @@ impl Default for AnimationPlayer {
         Self::new().expect("Failed to create animation player")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headless_play_records_frames() {
+        let mut player = AnimationPlayer::headless(40, 12).unwrap();
+        player.play(RocketAnimation::new(Duration::from_secs(1))).unwrap();
+
+        let frames = player.take_frames();
+        assert_eq!(frames.len(), 60);
+        assert_eq!(frames[0].grid.width(), 40);
+        assert_eq!(frames[0].grid.height(), 12);
+        assert_ne!(frames[0], frames[59]);
+        assert!(player.frames().is_empty());
+    }
+
+    #[test]
+    fn test_headless_time_is_simulated() {
+        let mut player = AnimationPlayer::headless(20, 5).unwrap();
+        let started = Instant::now();
+        player.play_for(SpinnerAnimation::new(), Duration::from_secs(10)).unwrap();
+
+        assert_eq!(player.frames().len(), 600);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let mut remaining = 3;
+        player
+            .play_while(SpinnerAnimation::new(), || {
+                remaining -= 1;
+                remaining >= 0
+            })
+            .unwrap();
+        assert_eq!(player.frames().len(), 603);
+    }
+
+    #[test]
+    fn test_headless_records_output_pane() {
+        let mut player = AnimationPlayer::headless(20, 8).unwrap();
+        let mut pane = OutputPane::new(2);
+        let mut lines = vec!["two", "one"];
+
+        player
+            .play_with_output(SpinnerAnimation::new(), &mut pane, |pane| match lines.pop() {
+                Some(line) => {
+                    pane.push(line, None, false);
+                    true
+                }
+                None => false,
+            })
+            .unwrap();
+
+        let frames = player.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].grid.height(), 6);
+        assert_eq!(frames[1].text, vec!["one", "two"]);
+    }
+}
@@ -0,0 +1,209 @@
+// Progress bar animation driven by a running command
+
+use super::Animation;
+use crate::braille::{BrailleGrid, Color};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Completed and total work units shared between the animation and its handle
+#[derive(Debug, Default)]
+struct ProgressState {
+    completed: AtomicUsize,
+    /// Total number of units, 0 while unknown
+    total: AtomicUsize,
+}
+
+/// Updates a [`ProgressAnimation`] from another part of the program
+#[derive(Debug, Clone)]
+pub struct ProgressHandle {
+    state: Arc<ProgressState>,
+}
+
+impl ProgressHandle {
+    /// Record one more completed unit
+    pub fn advance(&self) {
+        self.state.completed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Set the number of completed units
+    pub fn set_completed(&self, completed: usize) {
+        self.state.completed.store(completed, Ordering::Relaxed);
+    }
+
+    /// Set the total number of units once it is known
+    pub fn set_total(&self, total: usize) {
+        self.state.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Get the number of completed units
+    pub fn completed(&self) -> usize {
+        self.state.completed.load(Ordering::Relaxed)
+    }
+
+    /// Get the total number of units, if known
+    pub fn total(&self) -> Option<usize> {
+        match self.state.total.load(Ordering::Relaxed) {
+            0 => None,
+            total => Some(total),
+        }
+    }
+}
+
+/// Progress bar that fills as work completes
+///
+/// With a known total the bar fills proportionally; otherwise a block sweeps
+/// back and forth and a tick is drawn beneath the bar per completed unit.
+pub struct ProgressAnimation {
+    handle: ProgressHandle,
+    elapsed: Duration,
+    /// Fill the bar over this time instead of following the handle
+    duration: Option<Duration>,
+}
+
+impl ProgressAnimation {
+    /// Create a progress bar driven through its [`handle`](Self::handle)
+    pub fn new() -> Self {
+        Self {
+            handle: ProgressHandle {
+                state: Arc::new(ProgressState::default()),
+            },
+            elapsed: Duration::ZERO,
+            duration: None,
+        }
+    }
+
+    /// Create a progress bar that fills itself over `duration`
+    pub fn timed(duration: Duration) -> Self {
+        Self {
+            duration: Some(duration),
+            ..Self::new()
+        }
+    }
+
+    /// Get a handle for reporting progress
+    pub fn handle(&self) -> ProgressHandle {
+        self.handle.clone()
+    }
+
+    /// Fraction of the bar to fill, or `None` while the total is unknown
+    fn fraction(&self) -> Option<f32> {
+        if let Some(duration) = self.duration {
+            return Some((self.elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0));
+        }
+        let total = self.handle.total()?;
+        Some((self.handle.completed() as f32 / total as f32).min(1.0))
+    }
+}
+
+impl Default for ProgressAnimation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Animation for ProgressAnimation {
+    fn update(&mut self, delta_time: Duration) -> bool {
+        self.elapsed += delta_time;
+        match self.duration {
+            Some(duration) => self.elapsed < duration,
+            None => true, // Runs until the command finishes
+        }
+    }
+
+    fn render(&self, grid: &mut BrailleGrid) {
+        let width = grid.dot_width();
+        let height = grid.dot_height();
+        if width < 8 || height < 8 {
+            return;
+        }
+
+        // Bar geometry: 3/4 of the width, centered
+        let bar_width = width * 3 / 4;
+        let left = (width - bar_width) / 2;
+        let right = left + bar_width - 1;
+        let bar_height = 8.min(height / 2);
+        let top = (height - bar_height) / 2;
+        let bottom = top + bar_height - 1;
+
+        // Outline
+        for x in left..=right {
+            grid.set_dot_with_color(x, top, Color::WHITE);
+            grid.set_dot_with_color(x, bottom, Color::WHITE);
+        }
+        for y in top..=bottom {
+            grid.set_dot_with_color(left, y, Color::WHITE);
+            grid.set_dot_with_color(right, y, Color::WHITE);
+        }
+
+        // Interior
+        let inner_left = left + 2;
+        let inner_width = bar_width.saturating_sub(4);
+        let (fill_start, fill_end, color) = match self.fraction() {
+            Some(fraction) => {
+                let filled = (inner_width as f32 * fraction) as usize;
+                (inner_left, inner_left + filled, Color::GREEN)
+            }
+            None => {
+                // Sweep a block back and forth
+                let block = (inner_width / 5).max(1);
+                let travel = inner_width.saturating_sub(block).max(1) as f32;
+                let phase = (self.elapsed.as_secs_f32() * 0.8).fract();
+                let bounce = if phase < 0.5 { phase * 2.0 } else { 2.0 - phase * 2.0 };
+                let start = inner_left + (travel * bounce) as usize;
+                (start, start + block, Color::CYAN)
+            }
+        };
+        for x in fill_start..fill_end.min(inner_left + inner_width) {
+            for y in (top + 2)..=(bottom.saturating_sub(2)) {
+                grid.set_dot_with_color(x, y, color);
+            }
+        }
+
+        // One tick per completed unit beneath the bar, wrapping onto new rows
+        let ticks_top = bottom + 3;
+        let per_row = (bar_width / 2).max(1);
+        for i in 0..self.handle.completed() {
+            let x = left + (i % per_row) * 2;
+            let y = ticks_top + (i / per_row) * 2;
+            if y >= height {
+                break;
+            }
+            grid.set_dot_with_color(x, y, Color::new(150, 255, 150));
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Progress"
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_drives_fraction() {
+        let animation = ProgressAnimation::new();
+        let handle = animation.handle();
+        assert_eq!(animation.fraction(), None);
+
+        handle.advance();
+        handle.advance();
+        handle.set_total(4);
+        assert_eq!(handle.completed(), 2);
+        assert_eq!(animation.fraction(), Some(0.5));
+    }
+
+    #[test]
+    fn test_timed_progress_finishes() {
+        let mut animation = ProgressAnimation::timed(Duration::from_millis(100));
+        assert!(animation.update(Duration::from_millis(50)));
+        assert!(!animation.update(Duration::from_millis(60)));
+        assert_eq!(animation.fraction(), Some(1.0));
+    }
+}
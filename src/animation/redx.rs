@@ -0,0 +1,83 @@
+// Red X that shakes its head, for failed commands
+
+use super::Animation;
+use crate::braille::{BrailleGrid, Color};
+use std::time::Duration;
+
+/// Big red X that slams in and shakes side to side
+pub struct RedXAnimation {
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl RedXAnimation {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+
+    /// Horizontal offset in dots: a damped "no no no" shake
+    fn shake_offset(&self) -> isize {
+        let t = self.elapsed.as_secs_f32();
+        let damping = (1.0 - t / self.duration.as_secs_f32()).max(0.0);
+        ((t * 28.0).sin() * 6.0 * damping).round() as isize
+    }
+}
+
+impl Default for RedXAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(1200))
+    }
+}
+
+impl Animation for RedXAnimation {
+    fn update(&mut self, delta_time: Duration) -> bool {
+        self.elapsed += delta_time;
+        self.elapsed < self.duration
+    }
+
+    fn render(&self, grid: &mut BrailleGrid) {
+        let width = grid.dot_width() as isize;
+        let height = grid.dot_height() as isize;
+
+        // Slam in: grow to full size over the first 150ms
+        let grow = (self.elapsed.as_secs_f32() / 0.15).min(1.0);
+        let full = (width.min(height) as f32 * 0.8) as isize;
+        let size = ((full as f32 * grow) as isize).max(2);
+        let half = size / 2;
+
+        let center_x = width / 2 + self.shake_offset();
+        let center_y = height / 2;
+        let thickness = (size / 10).max(1);
+
+        // Two thick diagonals
+        for i in -half..=half {
+            for t in -thickness..=thickness {
+                let points = [
+                    (center_x + i + t, center_y + i),
+                    (center_x + i + t, center_y - i),
+                ];
+                for (x, y) in points {
+                    if x >= 0 && y >= 0 && x < width && y < height {
+                        let color = if t == 0 {
+                            Color::new(255, 60, 60)
+                        } else {
+                            Color::new(200, 0, 0)
+                        };
+                        grid.set_dot_with_color(x as usize, y as usize, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Red X"
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+}
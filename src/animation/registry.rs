@@ -0,0 +1,476 @@
+// Registry of animations that can be looked up by name
+//
+// Built-in animations register themselves here, and so can animations from
+// other crates. The CLI, the config loader and the wrappers all resolve
+// animation names through the global registry.
+
+use super::{
+    Animation, BabyAnnouncementAnimation, ConfettiAnimation, DownloadAnimation,
+    ExplosionAnimation, FireworksAnimation, GameOverAnimation, MatrixRainAnimation,
+    MergeAnimation, ProgressAnimation, RabbitAnimation, RedXAnimation, RocketAnimation,
+    SaveAnimation, SpinnerAnimation, TiltAnimation, TrophyAnimation,
+};
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard};
+use std::time::Duration;
+
+/// Builds an animation from creation parameters
+pub type AnimationFactory =
+    Arc<dyn Fn(&AnimationParams) -> Result<Box<dyn Animation>> + Send + Sync>;
+
+/// Parameters passed to an animation factory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnimationParams {
+    /// Length of the animation; the registry fills in the entry's default
+    pub duration: Option<Duration>,
+    /// Text to display, e.g. command output for `matrix`
+    pub text: Option<String>,
+    /// File to load frames from, for `frame-based`
+    pub path: Option<PathBuf>,
+}
+
+impl AnimationParams {
+    /// Parameters with a specific duration
+    pub fn with_duration(duration: Duration) -> Self {
+        Self {
+            duration: Some(duration),
+            ..Default::default()
+        }
+    }
+}
+
+/// Description of a registered animation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationInfo {
+    pub name: String,
+    pub description: String,
+    /// Default length, or `None` for animations that run until stopped
+    pub default_duration: Option<Duration>,
+    /// Free-form labels such as `running`, `success` or `celebration`
+    pub tags: Vec<String>,
+}
+
+impl AnimationInfo {
+    /// Describe an animation that runs until stopped and has no tags
+    pub fn new(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            default_duration: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Set the default duration
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.default_duration = Some(duration);
+        self
+    }
+
+    /// Add tags
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags.extend(tags.iter().map(|tag| tag.to_string()));
+        self
+    }
+
+    /// Check if the animation has a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+struct Entry {
+    info: AnimationInfo,
+    factory: AnimationFactory,
+}
+
+/// Named animation factories
+///
+/// # Example
+/// ```
+/// use crabcrust::animation::{AnimationInfo, AnimationParams, AnimationRegistry, SpinnerAnimation};
+/// use crabcrust::Color;
+///
+/// AnimationRegistry::register_global(
+///     AnimationInfo::new("red-spinner", "Spinner in red").with_tags(&["running"]),
+///     |_params| Ok(Box::new(SpinnerAnimation::with_color(Color::RED))),
+/// );
+///
+/// let spinner = AnimationRegistry::create_global("red-spinner", &AnimationParams::default());
+/// assert!(spinner.is_ok());
+/// ```
+#[derive(Default)]
+pub struct AnimationRegistry {
+    entries: Vec<Entry>,
+}
+
+impl AnimationRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry containing every built-in animation
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        register_builtins(&mut registry);
+        registry
+    }
+
+    /// Create an animation by name from the process-wide registry
+    ///
+    /// The registry is only locked to look the factory up, not while it runs.
+    pub fn create_global(name: &str, params: &AnimationParams) -> Result<Box<dyn Animation>> {
+        let (factory, params) = Self::read_global().factory(name, params)?;
+        factory(&params)
+    }
+
+    /// Get the animations in the process-wide registry, in registration order
+    pub fn global_animations() -> Vec<AnimationInfo> {
+        Self::read_global().animations().cloned().collect()
+    }
+
+    /// Get the names of the animations in the process-wide registry
+    pub fn global_names() -> Vec<String> {
+        Self::read_global()
+            .animations()
+            .map(|info| info.name.clone())
+            .collect()
+    }
+
+    /// Lock the process-wide registry, populated with the built-ins, for reading
+    fn read_global() -> RwLockReadGuard<'static, AnimationRegistry> {
+        Self::global_lock()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register an animation in the process-wide registry
+    pub fn register_global<F>(info: AnimationInfo, factory: F)
+    where
+        F: Fn(&AnimationParams) -> Result<Box<dyn Animation>> + Send + Sync + 'static,
+    {
+        Self::global_lock()
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .register(info, factory);
+    }
+
+    fn global_lock() -> &'static RwLock<AnimationRegistry> {
+        static GLOBAL: OnceLock<RwLock<AnimationRegistry>> = OnceLock::new();
+        GLOBAL.get_or_init(|| RwLock::new(Self::with_builtins()))
+    }
+
+    /// Register an animation, replacing any existing one with the same name
+    pub fn register<F>(&mut self, info: AnimationInfo, factory: F)
+    where
+        F: Fn(&AnimationParams) -> Result<Box<dyn Animation>> + Send + Sync + 'static,
+    {
+        let entry = Entry {
+            info,
+            factory: Arc::new(factory),
+        };
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.info.name == entry.info.name)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Check if an animation is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.info(name).is_some()
+    }
+
+    /// Get the description of an animation
+    pub fn info(&self, name: &str) -> Option<&AnimationInfo> {
+        self.entries
+            .iter()
+            .map(|e| &e.info)
+            .find(|info| info.name == name)
+    }
+
+    /// Get all registered animations in registration order
+    pub fn animations(&self) -> impl Iterator<Item = &AnimationInfo> {
+        self.entries.iter().map(|e| &e.info)
+    }
+
+    /// Get the names of all registered animations
+    pub fn names(&self) -> Vec<&str> {
+        self.animations().map(|info| info.name.as_str()).collect()
+    }
+
+    /// Get the animations carrying a tag
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a AnimationInfo> {
+        self.animations().filter(move |info| info.has_tag(tag))
+    }
+
+    /// Create an animation by name
+    ///
+    /// A missing duration in `params` is replaced by the entry's default.
+    pub fn create(&self, name: &str, params: &AnimationParams) -> Result<Box<dyn Animation>> {
+        let (factory, params) = self.factory(name, params)?;
+        factory(&params)
+    }
+
+    /// Get the factory for an animation and the parameters to call it with
+    fn factory(
+        &self,
+        name: &str,
+        params: &AnimationParams,
+    ) -> Result<(AnimationFactory, AnimationParams)> {
+        let Some(entry) = self.entries.iter().find(|e| e.info.name == name) else {
+            bail!(
+                "Unknown animation '{}' (expected one of: {})",
+                name,
+                self.names().join(", ")
+            );
+        };
+
+        let params = AnimationParams {
+            duration: params.duration.or(entry.info.default_duration),
+            ..params.clone()
+        };
+        Ok((Arc::clone(&entry.factory), params))
+    }
+}
+
+/// Register every built-in animation
+fn register_builtins(registry: &mut AnimationRegistry) {
+    fn timed<A: Animation + 'static>(
+        new: fn(Duration) -> A,
+    ) -> impl Fn(&AnimationParams) -> Result<Box<dyn Animation>> {
+        move |params| {
+            let duration = params.duration.unwrap_or(Duration::from_secs(2));
+            Ok(Box::new(new(duration)))
+        }
+    }
+
+    registry.register(
+        AnimationInfo::new("spinner", "Spinning circle of Braille dots").with_tags(&["running"]),
+        |_| Ok(Box::new(SpinnerAnimation::new())),
+    );
+    registry.register(
+        AnimationInfo::new("rocket", "Rocket launching into the sky")
+            .with_duration(Duration::from_secs(2))
+            .with_tags(&["success", "celebration", "push"]),
+        timed(RocketAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("save", "Floppy disk with a filling progress bar")
+            .with_duration(Duration::from_millis(1500))
+            .with_tags(&["success", "commit"]),
+        timed(SaveAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("download", "Arrow dropping into a tray")
+            .with_duration(Duration::from_millis(1500))
+            .with_tags(&["success", "pull"]),
+        timed(DownloadAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("merge", "Two branches joining into one")
+            .with_duration(Duration::from_millis(1500))
+            .with_tags(&["success", "merge"]),
+        timed(MergeAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("rabbit", "White rabbit hopping past: I'm late!")
+            .with_duration(Duration::from_millis(2000))
+            .with_tags(&["success", "pull"]),
+        timed(RabbitAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("fireworks", "Fireworks bursting across the sky")
+            .with_duration(Duration::from_millis(3000))
+            .with_tags(&["success", "celebration", "push"]),
+        timed(FireworksAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new(
+            "baby",
+            "Birth announcement: congratulations, it's a commit!",
+        )
+        .with_duration(Duration::from_millis(2500))
+        .with_tags(&["success", "celebration", "commit"]),
+        timed(BabyAnnouncementAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("confetti", "Confetti raining down")
+            .with_duration(Duration::from_millis(2000))
+            .with_tags(&["success", "celebration", "commit", "push"]),
+        timed(ConfettiAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("trophy", "Shining trophy for a champion")
+            .with_duration(Duration::from_millis(2000))
+            .with_tags(&["success", "celebration", "push"]),
+        timed(TrophyAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("matrix", "Matrix rain decoding text (command output)")
+            .with_duration(Duration::from_secs(3))
+            .with_tags(&["success", "output"]),
+        |params| {
+            let text = params
+                .text
+                .clone()
+                .unwrap_or_else(|| "The Matrix has you...".to_string());
+            let duration = params.duration.unwrap_or(Duration::from_secs(3));
+            Ok(Box::new(MatrixRainAnimation::with_params(
+                text, duration, 0.7, 0.5,
+            )))
+        },
+    );
+    registry.register(
+        AnimationInfo::new("progress", "Progress bar filling up")
+            .with_duration(Duration::from_secs(2))
+            .with_tags(&["running", "build"]),
+        timed(ProgressAnimation::timed),
+    );
+    registry.register(
+        AnimationInfo::new("explosion", "Blast with flying debris that fades to smoke")
+            .with_duration(Duration::from_millis(1500))
+            .with_tags(&["failure"]),
+        timed(ExplosionAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("red-x", "Big red X shaking its head")
+            .with_duration(Duration::from_millis(1200))
+            .with_tags(&["failure"]),
+        timed(RedXAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("tilt", "Pinball TILT flashing on a dot-matrix display")
+            .with_duration(Duration::from_millis(1800))
+            .with_tags(&["failure"]),
+        timed(TiltAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new("game-over", "Arcade GAME OVER screen")
+            .with_duration(Duration::from_secs(2))
+            .with_tags(&["failure"]),
+        timed(GameOverAnimation::new),
+    );
+    registry.register(
+        AnimationInfo::new(
+            "frame-based",
+            "Pre-rendered frames loaded from a GIF `path`",
+        )
+        .with_tags(&["frames"]),
+        load_frames,
+    );
+}
+
+/// Factory for `frame-based`: convert the GIF at `params.path`
+#[cfg(any(feature = "gif", feature = "video"))]
+fn load_frames(params: &AnimationParams) -> Result<Box<dyn Animation>> {
+    use super::FrameBasedAnimation;
+    use crate::video::converter;
+
+    let Some(path) = &params.path else {
+        bail!("The frame-based animation needs a GIF path");
+    };
+    // Same cell size and threshold as the bundled DMD animations
+    let frames = converter::gif_to_frames(path, 124, 19, 50)?;
+    Ok(Box::new(FrameBasedAnimation::from_braille_frames(
+        frames, false,
+    )))
+}
+
+/// Factory for `frame-based`: loading frames needs the `gif` feature
+#[cfg(not(any(feature = "gif", feature = "video")))]
+fn load_frames(_params: &AnimationParams) -> Result<Box<dyn Animation>> {
+    bail!("The frame-based animation requires the 'gif' feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtins_registered() {
+        let registry = AnimationRegistry::with_builtins();
+        for name in [
+            "spinner",
+            "rocket",
+            "save",
+            "download",
+            "merge",
+            "rabbit",
+            "fireworks",
+            "baby",
+            "confetti",
+            "trophy",
+            "matrix",
+            "progress",
+            "explosion",
+            "red-x",
+            "tilt",
+            "game-over",
+            "frame-based",
+        ] {
+            assert!(registry.contains(name), "{} is not registered", name);
+        }
+    }
+
+    #[test]
+    fn test_create_uses_default_duration() {
+        let registry = AnimationRegistry::with_builtins();
+
+        let rocket = registry
+            .create("rocket", &AnimationParams::default())
+            .unwrap();
+        assert_eq!(rocket.duration(), Some(Duration::from_secs(2)));
+
+        let params = AnimationParams::with_duration(Duration::from_millis(500));
+        let rocket = registry.create("rocket", &params).unwrap();
+        assert_eq!(rocket.duration(), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_unknown_animation() {
+        let registry = AnimationRegistry::with_builtins();
+        let err = registry
+            .create("lazer", &AnimationParams::default())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Unknown animation 'lazer'"));
+    }
+
+    #[test]
+    fn test_create_global_releases_lock() {
+        // A factory may register animations itself without deadlocking
+        AnimationRegistry::register_global(AnimationInfo::new("test-nested", "Nested"), |_| {
+            AnimationRegistry::register_global(AnimationInfo::new("test-inner", "Inner"), |_| {
+                Ok(Box::new(SpinnerAnimation::new()))
+            });
+            Ok(Box::new(SpinnerAnimation::new()))
+        });
+
+        let nested = AnimationRegistry::create_global("test-nested", &AnimationParams::default());
+        assert!(nested.is_ok());
+        assert!(AnimationRegistry::global_names().contains(&"test-inner".to_string()));
+    }
+
+    #[test]
+    fn test_register_replaces_and_tags() {
+        let mut registry = AnimationRegistry::new();
+        registry.register(AnimationInfo::new("custom", "First"), |_| {
+            Ok(Box::new(SpinnerAnimation::new()))
+        });
+        registry.register(
+            AnimationInfo::new("custom", "Second").with_tags(&["running"]),
+            |_| Ok(Box::new(SpinnerAnimation::new())),
+        );
+
+        assert_eq!(registry.names(), vec!["custom"]);
+        assert_eq!(registry.info("custom").unwrap().description, "Second");
+        assert_eq!(registry.tagged("running").count(), 1);
+        assert_eq!(registry.tagged("success").count(), 0);
+    }
+}
@@ -0,0 +1,57 @@
+// Big dot-matrix text for DMD-style messages
+//
+// Helpers over the embedded 5×7 font for animations that fill their area with
+// a word or two.
+
+use crate::braille::{BrailleGrid, Color, Font, TextAlign, TextStyle};
+use std::sync::OnceLock;
+
+/// Get the 5×7 font, built once
+pub(crate) fn dmd_font() -> &'static Font {
+    static FONT: OnceLock<Font> = OnceLock::new();
+    FONT.get_or_init(Font::standard)
+}
+
+/// Largest scale at which `text` fits in the given area (at least 1)
+pub(crate) fn fit_scale(text: &str, max_width: usize, max_height: usize) -> usize {
+    let (width, height) = TextStyle::new(dmd_font()).measure(text);
+    (max_width / width.max(1))
+        .min(max_height / height.max(1))
+        .max(1)
+}
+
+/// Draw `text` centered horizontally around `center_x`, top edge at `y`
+pub(crate) fn draw_text_centered(
+    grid: &mut BrailleGrid,
+    text: &str,
+    center_x: i32,
+    y: i32,
+    scale: usize,
+    color: Color,
+) {
+    let style = TextStyle::new(dmd_font())
+        .with_scale(scale)
+        .with_align(TextAlign::Center);
+    grid.draw_text(text, center_x, y, &style, color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_scale() {
+        assert_eq!(fit_scale("TILT", 80, 40), 3);
+        assert_eq!(fit_scale("TILT", 10, 4), 1);
+    }
+
+    #[test]
+    fn test_draw_text_centered() {
+        let mut grid = BrailleGrid::new(4, 2);
+        draw_text_centered(&mut grid, "T", 1, 0, 1, Color::RED);
+        // The T's bar reaches the grid, the rest is off the left edge
+        assert!(!grid.is_empty(0, 0));
+        assert_eq!(grid.get_color(0, 0), Some(Color::RED));
+        assert!(grid.is_empty(3, 1));
+    }
+}
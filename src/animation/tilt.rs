@@ -0,0 +1,99 @@
+// Pinball-style TILT message for failed commands
+
+use super::text::{dmd_font, draw_text_centered, fit_scale};
+use super::Animation;
+use crate::braille::{BrailleGrid, Color};
+use std::time::Duration;
+
+/// Classic amber dot-matrix display color
+const DMD_AMBER: Color = Color::new(255, 120, 0);
+
+/// "TILT" flashing on a dot-matrix display, jolted like a nudged table
+pub struct TiltAnimation {
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl TiltAnimation {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+
+    /// Whether the message is lit: rapid flashing, then steady
+    fn is_lit(&self) -> bool {
+        let t = self.elapsed.as_secs_f32();
+        if t > self.duration.as_secs_f32() * 0.6 {
+            return true;
+        }
+        ((t / 0.18) as usize).is_multiple_of(2)
+    }
+
+    /// Sideways jolt at the start of each flash
+    fn jolt(&self) -> i32 {
+        let t = self.elapsed.as_secs_f32();
+        if t > self.duration.as_secs_f32() * 0.6 {
+            return 0;
+        }
+        let into_flash = (t % 0.36) / 0.36;
+        if into_flash < 0.15 {
+            if ((t / 0.36) as usize).is_multiple_of(2) {
+                3
+            } else {
+                -3
+            }
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for TiltAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(1800))
+    }
+}
+
+impl Animation for TiltAnimation {
+    fn update(&mut self, delta_time: Duration) -> bool {
+        self.elapsed += delta_time;
+        self.elapsed < self.duration
+    }
+
+    fn render(&self, grid: &mut BrailleGrid) {
+        let width = grid.dot_width();
+        let height = grid.dot_height();
+        if width < 4 || height < 4 {
+            return;
+        }
+
+        // Dotted DMD frame
+        for x in (0..width).step_by(2) {
+            grid.set_dot_with_color(x, 0, DMD_AMBER);
+            grid.set_dot_with_color(x, height - 1, DMD_AMBER);
+        }
+        for y in (0..height).step_by(2) {
+            grid.set_dot_with_color(0, y, DMD_AMBER);
+            grid.set_dot_with_color(width - 1, y, DMD_AMBER);
+        }
+
+        if !self.is_lit() {
+            return;
+        }
+
+        let scale = fit_scale("TILT", width * 4 / 5, height * 3 / 4);
+        let top = (height.saturating_sub(dmd_font().line_height() * scale) / 2) as i32;
+        let center_x = (width / 2) as i32 + self.jolt();
+        draw_text_centered(grid, "TILT", center_x, top, scale, DMD_AMBER);
+    }
+
+    fn name(&self) -> &str {
+        "Tilt"
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+}
@@ -49,75 +49,30 @@ impl Animation for TrophyAnimation {
         // Trophy cup (upper part)
         if reveal_height > 10 {
             for dy in -30..-10 {
-                if trophy_y + dy < 0 {
-                    continue;
-                }
                 let width = 20 + ((dy + 30) / 2);
-                for dx in -width..width {
-                    let x = (trophy_x + dx).max(0) as usize;
-                    let y = (trophy_y + dy).max(0) as usize;
-                    if x < grid.dot_width() && y < grid.dot_height() {
-                        // Gradient effect
-                        let color = if dx.abs() < width - 2 {
-                            gold
-                        } else {
-                            dark_gold
-                        };
-                        grid.set_dot_with_color(x, y, color);
-                    }
-                }
+                // Gradient effect: darker at the edges
+                grid.fill_rect(trophy_x - width, trophy_y + dy, 2 * width, 1, dark_gold);
+                grid.fill_rect(trophy_x - width + 3, trophy_y + dy, 2 * width - 5, 1, gold);
             }
 
             // Trophy rim (top edge)
-            for dx in -25..25 {
-                let x = (trophy_x + dx).max(0) as usize;
-                let y = (trophy_y - 30).max(0) as usize;
-                if x < grid.dot_width() && y < grid.dot_height() {
-                    grid.set_dot_with_color(x, y, gold);
-                }
-                if y > 0 {
-                    grid.set_dot_with_color(x, y - 1, gold);
-                }
-            }
+            grid.fill_rect(trophy_x - 25, trophy_y - 31, 50, 2, gold);
 
             // Handles (on sides)
-            for side in [-1, 1] {
-                for dy in -25..-15 {
-                    for dx in 0..8 {
-                        let x = (trophy_x + side * (20 + dx)).max(0) as usize;
-                        let y = (trophy_y + dy).max(0) as usize;
-                        if x < grid.dot_width() && y < grid.dot_height() {
-                            grid.set_dot_with_color(x, y, gold);
-                        }
-                    }
-                }
-            }
+            grid.fill_rect(trophy_x - 27, trophy_y - 25, 8, 10, gold);
+            grid.fill_rect(trophy_x + 20, trophy_y - 25, 8, 10, gold);
         }
 
         // Trophy stem
         if reveal_height > 40 {
-            for dy in -10..5 {
-                for dx in -4..4 {
-                    let x = (trophy_x + dx).max(0) as usize;
-                    let y = (trophy_y + dy).max(0) as usize;
-                    if x < grid.dot_width() && y < grid.dot_height() {
-                        grid.set_dot_with_color(x, y, gold);
-                    }
-                }
-            }
+            grid.fill_rect(trophy_x - 4, trophy_y - 10, 8, 15, gold);
         }
 
         // Trophy base
         if reveal_height > 50 {
             for dy in 5..15 {
                 let width = 15 + (dy - 5);
-                for dx in -width..width {
-                    let x = (trophy_x + dx).max(0) as usize;
-                    let y = (trophy_y + dy).max(0) as usize;
-                    if x < grid.dot_width() && y < grid.dot_height() {
-                        grid.set_dot_with_color(x, y, brown);
-                    }
-                }
+                grid.fill_rect(trophy_x - width, trophy_y + dy, 2 * width, 1, brown);
             }
         }
 
@@ -0,0 +1,158 @@
+// Characters a cell's dots are drawn with
+//
+// Braille shows all 2×4 dots of a cell, but some fonts draw it with gaps or
+// not at all. The other encodings use block characters at a lower resolution
+// (1×2, 2×2 or 2×3 per cell) or plain ASCII by how many dots are set; the
+// grid and everything drawing on it stay the same.
+
+use super::{dots_to_char, DOT_BITS};
+use anyhow::bail;
+use std::fmt;
+use std::str::FromStr;
+
+/// How a cell's 2×4 dot pattern is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellEncoding {
+    /// Braille patterns: every dot (2×4)
+    #[default]
+    Braille,
+    /// Upper and lower half blocks (1×2)
+    HalfBlock,
+    /// Quadrant blocks (2×2)
+    Quadrant,
+    /// Sextant blocks from Unicode 13 (2×3)
+    Sextant,
+    /// ASCII characters getting denser with more dots
+    Ascii,
+}
+
+/// Quadrant blocks by pattern: upper left, upper right, lower left, lower right
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// ASCII characters from no dots to all eight
+const ASCII_RAMP: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+impl CellEncoding {
+    /// Get the character showing a cell's dot pattern
+    pub fn encode(self, pattern: u8) -> char {
+        // Set dots in a column (0 or 1) within a range of rows
+        let any = |x: usize, rows: std::ops::Range<usize>| {
+            rows.into_iter().any(|y| pattern & DOT_BITS[x][y] != 0)
+        };
+        let bits = |parts: &[bool]| {
+            parts
+                .iter()
+                .enumerate()
+                .fold(0usize, |bits, (i, &set)| bits | (set as usize) << i)
+        };
+
+        match self {
+            Self::Braille => dots_to_char(pattern),
+            Self::HalfBlock => {
+                let top = any(0, 0..2) || any(1, 0..2);
+                let bottom = any(0, 2..4) || any(1, 2..4);
+                [' ', '▀', '▄', '█'][bits(&[top, bottom])]
+            }
+            Self::Quadrant => QUADRANTS[bits(&[any(0, 0..2), any(1, 0..2), any(0, 2..4), any(1, 2..4)])],
+            Self::Sextant => {
+                // The two middle dot rows share the middle sextant row
+                let rows = [0..1, 1..3, 3..4];
+                let parts: Vec<bool> = rows
+                    .iter()
+                    .flat_map(|rows| [any(0, rows.clone()), any(1, rows.clone())])
+                    .collect();
+                sextant(bits(&parts))
+            }
+            Self::Ascii => ASCII_RAMP[(pattern.count_ones() as usize * 9 + 4) / 8],
+        }
+    }
+}
+
+/// Get the sextant character for a pattern, bit 0 at the top left and bit 5
+/// at the bottom right
+///
+/// U+1FB00 onwards lists the patterns in order, leaving out the four that
+/// already had characters: empty, full and the left and right halves.
+fn sextant(pattern: usize) -> char {
+    match pattern {
+        0 => ' ',
+        0b010101 => '▌',
+        0b101010 => '▐',
+        0b111111 => '█',
+        _ => {
+            let skipped = (pattern > 0b010101) as u32 + (pattern > 0b101010) as u32;
+            char::from_u32(0x1FB00 + pattern as u32 - 1 - skipped).unwrap_or(' ')
+        }
+    }
+}
+
+impl fmt::Display for CellEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Braille => "braille",
+            Self::HalfBlock => "halfblock",
+            Self::Quadrant => "quadrant",
+            Self::Sextant => "sextant",
+            Self::Ascii => "ascii",
+        })
+    }
+}
+
+impl FromStr for CellEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "braille" => Ok(Self::Braille),
+            "halfblock" | "half-block" => Ok(Self::HalfBlock),
+            "quadrant" => Ok(Self::Quadrant),
+            "sextant" => Ok(Self::Sextant),
+            "ascii" => Ok(Self::Ascii),
+            _ => bail!(
+                "Unknown cell encoding '{}' (expected braille, halfblock, quadrant, sextant or ascii)",
+                s
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::braille::BrailleGrid;
+
+    /// Encode a one-cell grid with the given dots set
+    fn encode(encoding: CellEncoding, dots: &[(usize, usize)]) -> char {
+        let mut grid = BrailleGrid::new(1, 1);
+        for &(x, y) in dots {
+            grid.set_dot(x, y);
+        }
+        encoding.encode(grid.get_pattern(0, 0))
+    }
+
+    #[test]
+    fn test_block_encodings() {
+        assert_eq!(encode(CellEncoding::HalfBlock, &[]), ' ');
+        assert_eq!(encode(CellEncoding::HalfBlock, &[(1, 1)]), '▀');
+        assert_eq!(encode(CellEncoding::HalfBlock, &[(0, 0), (0, 3)]), '█');
+
+        assert_eq!(encode(CellEncoding::Quadrant, &[(1, 0)]), '▝');
+        assert_eq!(encode(CellEncoding::Quadrant, &[(0, 0), (1, 3)]), '▚');
+        assert_eq!(encode(CellEncoding::Quadrant, &[(0, 2), (1, 2)]), '▄');
+
+        assert_eq!(encode(CellEncoding::Sextant, &[(0, 0)]), '\u{1FB00}');
+        assert_eq!(encode(CellEncoding::Sextant, &[(0, 0), (0, 1), (0, 3)]), '▌');
+        assert_eq!(encode(CellEncoding::Sextant, &[(0, 0), (1, 0), (0, 2), (1, 2), (0, 3)]), '\u{1FB1D}');
+    }
+
+    #[test]
+    fn test_ascii_ramp() {
+        assert_eq!(encode(CellEncoding::Ascii, &[]), ' ');
+        assert_eq!(encode(CellEncoding::Ascii, &[(0, 0)]), '.');
+        let all: Vec<_> = (0..2).flat_map(|x| (0..4).map(move |y| (x, y))).collect();
+        assert_eq!(encode(CellEncoding::Ascii, &all), '@');
+        assert_eq!(encode(CellEncoding::Braille, &all), '⣿');
+    }
+}
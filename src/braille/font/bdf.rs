@@ -0,0 +1,233 @@
+// Reading fonts in the Glyph Bitmap Distribution Format (BDF)
+//
+// BDF is the plain-text bitmap font format of X11; most pixel fonts (Terminus,
+// Unifont, Spleen, the classic misc-fixed) are available in it. Only what
+// drawing needs is read: the font's ascent and descent, and each encoded
+// glyph's bounding box, advance and bitmap. Properties and metrics for
+// vertical writing are skipped.
+
+use super::{Font, FontGlyph};
+use anyhow::{bail, Context, Result};
+
+/// A glyph being read, between STARTCHAR and ENDCHAR
+#[derive(Default)]
+struct PendingGlyph {
+    encoding: Option<i64>,
+    advance: Option<i32>,
+    /// Width, height and offsets of the bitmap
+    bbx: Option<[i32; 4]>,
+    /// Rows read so far, once BITMAP started
+    bitmap: Option<Vec<u64>>,
+}
+
+/// Parse a BDF font
+pub(super) fn parse(source: &str) -> Result<Font> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    match lines.next() {
+        Some((_, line)) if line.starts_with("STARTFONT") => {}
+        _ => bail!("Not a BDF font (expected STARTFONT)"),
+    }
+
+    let mut name = String::from("bdf");
+    let (mut ascent, mut descent, mut bounding_box) = (None, None, None);
+    let mut font_glyphs = Vec::new();
+    let mut pending: Option<PendingGlyph> = None;
+
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let line = line.trim();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        // Bitmap rows until ENDCHAR
+        if let Some(glyph) = pending.as_mut() {
+            if let (Some(rows), false) = (glyph.bitmap.as_mut(), keyword == "ENDCHAR") {
+                rows.push(
+                    parse_row(line)
+                        .with_context(|| format!("Invalid bitmap row on line {}", line_number))?,
+                );
+                continue;
+            }
+        }
+
+        match keyword {
+            "FONT" => name = rest.to_string(),
+            "FONTBOUNDINGBOX" => bounding_box = Some(numbers::<4>(rest, line_number)?),
+            "FONT_ASCENT" => ascent = Some(numbers::<1>(rest, line_number)?[0]),
+            "FONT_DESCENT" => descent = Some(numbers::<1>(rest, line_number)?[0]),
+            "STARTCHAR" => pending = Some(PendingGlyph::default()),
+            "ENDFONT" => break,
+            _ => {}
+        }
+        let Some(glyph) = pending.as_mut() else {
+            continue;
+        };
+        match keyword {
+            // The encoding may be followed by a non-standard one
+            "ENCODING" => {
+                glyph.encoding = Some(
+                    rest.split_whitespace()
+                        .next()
+                        .unwrap_or("")
+                        .parse()
+                        .with_context(|| format!("Invalid ENCODING on line {}", line_number))?,
+                )
+            }
+            "DWIDTH" => glyph.advance = Some(numbers::<2>(rest, line_number)?[0]),
+            "BBX" => glyph.bbx = Some(numbers::<4>(rest, line_number)?),
+            "BITMAP" => glyph.bitmap = Some(Vec::new()),
+            "ENDCHAR" => {
+                if let Some(entry) = finish(
+                    pending.take().unwrap_or_default(),
+                    bounding_box,
+                    line_number,
+                )? {
+                    font_glyphs.push(entry);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Without the properties, the font's bounding box tells the line height
+    let (ascent, descent) = match (ascent, descent, bounding_box) {
+        (Some(ascent), Some(descent), _) => (ascent, descent),
+        (_, _, Some([_, height, _, y_offset])) => (height + y_offset, -y_offset),
+        _ => bail!("BDF font has neither FONT_ASCENT and FONT_DESCENT nor FONTBOUNDINGBOX"),
+    };
+
+    let mut font = Font::new(&name, ascent, descent);
+    for (ch, glyph) in font_glyphs {
+        font.insert(ch, glyph);
+    }
+    Ok(font)
+}
+
+/// Turn a glyph that was read into a character and its glyph, or `None` if
+/// it has no Unicode encoding
+fn finish(
+    glyph: PendingGlyph,
+    bounding_box: Option<[i32; 4]>,
+    line_number: usize,
+) -> Result<Option<(char, FontGlyph)>> {
+    let Some(ch) = glyph
+        .encoding
+        .and_then(|code| u32::try_from(code).ok())
+        .and_then(char::from_u32)
+    else {
+        return Ok(None);
+    };
+    let Some([width, height, x_offset, y_offset]) = glyph.bbx.or(bounding_box) else {
+        bail!("Glyph ending on line {} has no BBX", line_number);
+    };
+    if !(0..=64).contains(&width) {
+        bail!(
+            "Glyph ending on line {} is {} dots wide (at most 64 are supported)",
+            line_number,
+            width
+        );
+    }
+
+    // Rows are padded to whole bytes on the right
+    let padding = (width as u32).div_ceil(8) * 8 - width as u32;
+    let rows: Vec<u64> = glyph
+        .bitmap
+        .unwrap_or_default()
+        .iter()
+        .map(|row| row >> padding)
+        .collect();
+    if rows.len() != height.max(0) as usize {
+        bail!(
+            "Glyph ending on line {} has {} bitmap rows, expected {}",
+            line_number,
+            rows.len(),
+            height
+        );
+    }
+
+    Ok(Some((
+        ch,
+        FontGlyph {
+            width: width as usize,
+            rows,
+            x_offset,
+            y_offset,
+            advance: glyph.advance.unwrap_or(x_offset + width),
+        },
+    )))
+}
+
+/// Parse a bitmap row of hex digits
+fn parse_row(line: &str) -> Result<u64> {
+    if line.len() > 16 {
+        bail!("Row '{}' is wider than 64 dots", line);
+    }
+    Ok(u64::from_str_radix(line, 16)?)
+}
+
+/// Parse the first `N` numbers of a line
+fn numbers<const N: usize>(text: &str, line_number: usize) -> Result<[i32; N]> {
+    let mut values = [0; N];
+    let mut words = text.split_whitespace();
+    for value in values.iter_mut() {
+        *value = words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .with_context(|| format!("Expected {} numbers on line {}", N, line_number))?;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::braille::{dots, BrailleGrid, Color, TextStyle};
+
+    const FONT: &str = "\
+STARTFONT 2.1
+FONT -test-tiny
+FONTBOUNDINGBOX 3 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR j
+ENCODING 106
+DWIDTH 3 0
+BBX 2 4 0 -1
+BITMAP
+40
+00
+40
+80
+ENDCHAR
+STARTCHAR unencoded
+ENCODING -1
+BBX 1 1 0 0
+BITMAP
+80
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn test_parse_bdf() {
+        let font = Font::parse_bdf(FONT).unwrap();
+        assert_eq!(font.name(), "-test-tiny");
+        assert_eq!(font.line_height(), 4);
+        assert!(font.has_glyph('j'));
+
+        // The j's tail hangs below the baseline
+        let mut grid = BrailleGrid::new(2, 1);
+        grid.draw_text("jj", 0, 0, &TextStyle::new(&font), Color::RED);
+        assert_eq!(dots(&grid), [".#..", "....", ".#..", "#..#"]);
+        assert_eq!(TextStyle::new(&font).measure("jj"), (5, 4));
+
+        assert!(Font::parse_bdf("not a font").is_err());
+        assert!(Font::parse_bdf(&FONT.replace("BBX 2 4 0 -1", "BBX 2 3 0 -1")).is_err());
+    }
+}
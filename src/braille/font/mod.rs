@@ -0,0 +1,435 @@
+// Bitmap fonts for drawing text in dots
+//
+// A font maps characters to glyph bitmaps placed on a baseline, the way BDF
+// describes them: each glyph sits at an offset from the pen and moves the pen
+// on by its advance. A few fonts are embedded; others load from BDF files.
+// `TextStyle` lays text out in a font (scale, alignment, spacing, kerning)
+// and `BrailleGrid::draw_text` draws it, clipped like the other shapes.
+
+mod bdf;
+mod builtin;
+
+use super::{BrailleGrid, Color};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Names of the embedded fonts, for [`Font::builtin`]
+pub const BUILTIN_FONTS: &[&str] = &["standard", "small", "dmd-tall"];
+
+/// Largest scale a [`TextStyle`] draws at
+pub const MAX_SCALE: usize = 256;
+
+/// A bitmap font
+#[derive(Debug, Clone)]
+pub struct Font {
+    name: String,
+    /// Rows above the baseline
+    ascent: i32,
+    /// Rows below the baseline
+    descent: i32,
+    glyphs: HashMap<char, FontGlyph>,
+    /// Extra dots between pairs of characters (negative moves them closer)
+    kerning: HashMap<(char, char), i32>,
+}
+
+/// One character's bitmap and placement
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FontGlyph {
+    width: usize,
+    /// Rows top to bottom, the most significant of `width` bits leftmost
+    rows: Vec<u64>,
+    /// From the pen to the bitmap's left edge
+    x_offset: i32,
+    /// From the baseline up to the bitmap's bottom edge
+    y_offset: i32,
+    /// How far the pen moves on
+    advance: i32,
+}
+
+impl FontGlyph {
+    /// A glyph standing on the baseline at the pen position
+    fn new(width: usize, rows: Vec<u64>, advance: i32) -> Self {
+        Self {
+            width,
+            rows,
+            x_offset: 0,
+            y_offset: 0,
+            advance,
+        }
+    }
+
+    fn is_set(&self, column: usize, row: usize) -> bool {
+        self.rows[row] & (1 << (self.width - 1 - column)) != 0
+    }
+
+    /// Get the first and last set columns of a row, relative to the pen
+    fn ink(&self, row: usize) -> Option<(i32, i32)> {
+        let mut columns = (0..self.width).filter(|&column| self.is_set(column, row));
+        let first = columns.next()?;
+        let last = columns.next_back().unwrap_or(first);
+        Some((first as i32 + self.x_offset, last as i32 + self.x_offset))
+    }
+}
+
+impl Font {
+    fn new(name: &str, ascent: i32, descent: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            ascent,
+            descent,
+            glyphs: HashMap::new(),
+            kerning: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, ch: char, glyph: FontGlyph) {
+        self.glyphs.insert(ch, glyph);
+    }
+
+    /// Get an embedded font by name (see [`BUILTIN_FONTS`])
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "standard" | "5x7" => Some(Self::standard()),
+            "small" | "3x5" => Some(Self::small()),
+            "dmd-tall" | "tall" => Some(Self::dmd_tall()),
+            _ => None,
+        }
+    }
+
+    /// Get an embedded font by name, or load a BDF file from `name` as a path
+    pub fn load(name: &str) -> Result<Self> {
+        match Self::builtin(name) {
+            Some(font) => Ok(font),
+            None => Self::load_bdf(name),
+        }
+    }
+
+    /// Load a font from a BDF file
+    pub fn load_bdf(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read font {}", path.display()))?;
+        Self::parse_bdf(&source).with_context(|| format!("Failed to load font {}", path.display()))
+    }
+
+    /// Parse a font in the BDF format
+    pub fn parse_bdf(source: &str) -> Result<Self> {
+        bdf::parse(source)
+    }
+
+    /// Add or move apart (positive) or together (negative) a pair of
+    /// characters
+    pub fn with_kerning_pair(mut self, left: char, right: char, adjust: i32) -> Self {
+        self.kerning.insert((left, right), adjust);
+        self
+    }
+
+    /// Get the font's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the height of a line in dots (before scaling)
+    pub fn line_height(&self) -> usize {
+        (self.ascent + self.descent).max(0) as usize
+    }
+
+    /// Check if the font can draw a character
+    pub fn has_glyph(&self, ch: char) -> bool {
+        self.glyph(ch).is_some()
+    }
+
+    /// Get a character's glyph, falling back to uppercase
+    fn glyph(&self, ch: char) -> Option<&FontGlyph> {
+        self.glyphs
+            .get(&ch)
+            .or_else(|| self.glyphs.get(&ch.to_ascii_uppercase()))
+    }
+
+    /// How far characters without a glyph move the pen
+    fn missing_advance(&self) -> i32 {
+        self.glyphs
+            .get(&' ')
+            .map(|glyph| glyph.advance)
+            .unwrap_or(self.ascent / 2)
+    }
+
+    /// Get the row a glyph's bitmap starts on, counted from the top of the
+    /// line
+    fn top(&self, glyph: &FontGlyph) -> i32 {
+        self.ascent - glyph.y_offset - glyph.rows.len() as i32
+    }
+
+    /// How much closer `right` can move to `left` so that their closest set
+    /// dots (also diagonally) are a dot apart
+    fn optical_kerning(&self, left: &FontGlyph, right: &FontGlyph) -> i32 {
+        let (left_top, right_top) = (self.top(left), self.top(right));
+        let mut closest = None;
+        for row in 0..left.rows.len() {
+            let Some((_, left_end)) = left.ink(row) else {
+                continue;
+            };
+            let y = left_top + row as i32;
+            for other in y - 1..=y + 1 {
+                let right_row = other - right_top;
+                if right_row < 0 || right_row >= right.rows.len() as i32 {
+                    continue;
+                }
+                if let Some((right_start, _)) = right.ink(right_row as usize) {
+                    let gap = left.advance + right_start - left_end - 1;
+                    closest = Some(closest.map_or(gap, |closest: i32| closest.min(gap)));
+                }
+            }
+        }
+        closest.map_or(0, |gap| (1 - gap).min(0))
+    }
+}
+
+/// Where text is drawn relative to its x position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    /// Starting at x
+    #[default]
+    Left,
+    /// Centered on x
+    Center,
+    /// Ending at x
+    Right,
+}
+
+/// How the space between characters is adjusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Kerning {
+    /// Every character moves the pen by its advance
+    None,
+    /// The font's kerning pairs
+    #[default]
+    Pairs,
+    /// The font's kerning pairs, and characters moved as close as they can
+    /// get while a dot apart (narrow glyphs like `I` and `!` take less room)
+    Optical,
+}
+
+/// How text is laid out: font, scale, alignment and spacing
+#[derive(Debug, Clone)]
+pub struct TextStyle<'a> {
+    font: &'a Font,
+    scale: usize,
+    align: TextAlign,
+    kerning: Kerning,
+    /// Extra dots between characters
+    spacing: i32,
+    /// Dots between lines
+    line_spacing: i32,
+}
+
+impl<'a> TextStyle<'a> {
+    /// Lay out text in `font`, unscaled and left-aligned
+    pub fn new(font: &'a Font) -> Self {
+        Self {
+            font,
+            scale: 1,
+            align: TextAlign::Left,
+            kerning: Kerning::default(),
+            spacing: 0,
+            line_spacing: 1,
+        }
+    }
+
+    /// Draw every dot of the font as a `scale` × `scale` block (up to [`MAX_SCALE`])
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.clamp(1, MAX_SCALE);
+        self
+    }
+
+    /// Align text relative to its x position
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Adjust the space between characters
+    pub fn with_kerning(mut self, kerning: Kerning) -> Self {
+        self.kerning = kerning;
+        self
+    }
+
+    /// Add dots between characters (before scaling; may be negative)
+    pub fn with_spacing(mut self, spacing: i32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Set the dots between lines (before scaling)
+    pub fn with_line_spacing(mut self, line_spacing: i32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    /// Get the width and height of `text` in dots
+    ///
+    /// Lines are separated by `\n`; the width is the widest line's.
+    pub fn measure(&self, text: &str) -> (usize, usize) {
+        let width = text
+            .lines()
+            .map(|line| self.layout(line).1)
+            .max()
+            .unwrap_or(0);
+        let lines = text.lines().count() as i64;
+        let height = lines * self.font.line_height() as i64
+            + (lines - 1).max(0) * self.line_spacing as i64;
+        (
+            width.max(0) as usize * self.scale,
+            height.max(0) as usize * self.scale,
+        )
+    }
+
+    /// Place a line's glyphs: each one's pen position, and the line's width
+    /// (before scaling)
+    fn layout(&self, line: &str) -> (Vec<(i64, &'a FontGlyph)>, i64) {
+        let font = self.font;
+        let mut placed = Vec::new();
+        let (mut pen, mut width) = (0, 0);
+        let mut previous: Option<(char, Option<&FontGlyph>)> = None;
+
+        for ch in line.chars() {
+            let glyph = font.glyph(ch);
+            if let Some((left, left_glyph)) = previous {
+                let advance = left_glyph.map_or(font.missing_advance(), |glyph| glyph.advance);
+                pen += advance as i64 + self.spacing as i64;
+                if self.kerning != Kerning::None {
+                    pen += font.kerning.get(&(left, ch)).copied().unwrap_or(0) as i64;
+                }
+                if let (Kerning::Optical, Some(left), Some(right)) =
+                    (self.kerning, left_glyph, glyph)
+                {
+                    pen += font.optical_kerning(left, right) as i64;
+                }
+            }
+            match glyph {
+                Some(glyph) => {
+                    placed.push((pen, glyph));
+                    width = width.max(pen + glyph.x_offset as i64 + glyph.width as i64);
+                }
+                None => width = width.max(pen + font.missing_advance() as i64),
+            }
+            previous = Some((ch, glyph));
+        }
+        (placed, width)
+    }
+}
+
+impl BrailleGrid {
+    /// Draw text with the top of its first line at `y`, clipped to the grid
+    ///
+    /// Lines are separated by `\n`. Characters the font has no glyph for are
+    /// left blank.
+    pub fn draw_text(&mut self, text: &str, x: i32, y: i32, style: &TextStyle, color: Color) {
+        let font = style.font;
+        // Laid out in i64 so that far off-grid text can't overflow
+        let scale = style.scale as i64;
+        let (x, y) = (x as i64, y as i64);
+        let (grid_width, grid_height) = (self.dot_width() as i64, self.dot_height() as i64);
+        let line_advance = (font.line_height() as i64 + style.line_spacing as i64) * scale;
+
+        for (index, line) in text.lines().enumerate() {
+            let (placed, width) = style.layout(line);
+            let left = match style.align {
+                TextAlign::Left => x,
+                TextAlign::Center => x - width * scale / 2,
+                TextAlign::Right => x - width * scale,
+            };
+            let line_top = y + index as i64 * line_advance;
+
+            for (pen, glyph) in placed {
+                let glyph_left = left + (pen + glyph.x_offset as i64) * scale;
+                let glyph_top = line_top + font.top(glyph) as i64 * scale;
+                for row in 0..glyph.rows.len() {
+                    for column in 0..glyph.width {
+                        let dot_x = glyph_left + column as i64 * scale;
+                        let dot_y = glyph_top + row as i64 * scale;
+                        let visible = (-scale + 1..grid_width).contains(&dot_x)
+                            && (-scale + 1..grid_height).contains(&dot_y);
+                        if visible && glyph.is_set(column, row) {
+                            // On the grid, so the block's corner fits an i32
+                            let size = scale as i32;
+                            self.fill_rect(dot_x as i32, dot_y as i32, size, size, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::braille::dots;
+
+    #[test]
+    fn test_measure() {
+        let font = Font::standard();
+        let style = TextStyle::new(&font);
+        assert_eq!(style.measure(""), (0, 0));
+        assert_eq!(style.measure("TILT"), (23, 7));
+        assert_eq!(style.clone().with_scale(2).measure("TILT"), (46, 14));
+        assert_eq!(style.measure("GAME\nOVER!"), (29, 15));
+
+        // Narrow glyphs take less room with optical kerning
+        let optical = style.clone().with_kerning(Kerning::Optical);
+        assert_eq!(optical.measure("TILT"), (19, 7));
+        let kerned = font.clone().with_kerning_pair('T', 'I', -2);
+        assert_eq!(TextStyle::new(&kerned).measure("TILT"), (21, 7));
+        assert_eq!(TextStyle::new(&Font::dmd_tall()).measure("JACKPOT").1, 14);
+    }
+
+    #[test]
+    fn test_draw_text_aligned_and_clipped() {
+        let font = Font::small();
+        let mut grid = BrailleGrid::new(5, 2);
+        // Right-aligned to x = 10, and "hi" in lowercase drawn as uppercase
+        grid.draw_text(
+            "hi",
+            10,
+            1,
+            &TextStyle::new(&font).with_align(TextAlign::Right),
+            Color::RED,
+        );
+        assert_eq!(
+            dots(&grid)[..7],
+            [
+                "..........",
+                "...#.#.###",
+                "...#.#..#.",
+                "...###..#.",
+                "...#.#..#.",
+                "...#.#.###",
+                "..........",
+            ]
+        );
+
+        // Scaled text off the top left edge is clipped
+        let mut grid = BrailleGrid::new(2, 1);
+        grid.draw_text(
+            "T",
+            -2,
+            -1,
+            &TextStyle::new(&font).with_scale(2),
+            Color::RED,
+        );
+        assert_eq!(dots(&grid), ["####", "##..", "##..", "##.."]);
+
+        // Far off the grid at the largest scale, nothing overflows or is drawn
+        let huge = TextStyle::new(&font)
+            .with_scale(usize::MAX)
+            .with_spacing(i32::MAX)
+            .with_line_spacing(i32::MAX)
+            .with_align(TextAlign::Center);
+        grid.clear();
+        grid.draw_text("WIDE\nTEXT", i32::MAX, i32::MIN, &huge, Color::RED);
+        grid.draw_text("WIDE", i32::MIN, 0, &huge, Color::RED);
+        assert!(dots(&grid).iter().all(|row| !row.contains('#')));
+    }
+}
@@ -11,6 +11,15 @@
 //
 // Unicode range: U+2800 to U+28FF (256 patterns)
 
+mod encoding;
+mod font;
+mod policy;
+mod shapes;
+
+pub use encoding::CellEncoding;
+pub use font::{Font, Kerning, TextAlign, TextStyle, BUILTIN_FONTS, MAX_SCALE};
+pub use policy::ColorPolicy;
+
 /// RGB Color for terminal rendering
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Color {
@@ -50,6 +59,22 @@ pub enum BrailleDot {
     Dot8 = 0b10000000,
 }
 
+/// Pattern bits of the dots in a cell, by column and row
+const DOT_BITS: [[u8; 4]; 2] = [
+    [
+        BrailleDot::Dot1 as u8,
+        BrailleDot::Dot2 as u8,
+        BrailleDot::Dot3 as u8,
+        BrailleDot::Dot7 as u8,
+    ],
+    [
+        BrailleDot::Dot4 as u8,
+        BrailleDot::Dot5 as u8,
+        BrailleDot::Dot6 as u8,
+        BrailleDot::Dot8 as u8,
+    ],
+];
+
 /// Convert dot pattern to Braille Unicode character
 ///
 /// # Arguments
@@ -91,6 +116,7 @@ pub fn dots_to_char(dots: u8) -> char {
 /// grid.set_dot(0, 0);
 /// assert_eq!(grid.get_char(0, 0), '⠁');
 /// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BrailleGrid {
     /// Width in terminal cells
     width: usize,
@@ -98,8 +124,22 @@ pub struct BrailleGrid {
     height: usize,
     /// Dot patterns for each cell (binary on/off)
     patterns: Vec<u8>,
-    /// Optional colors for each cell
+    /// Color drawn last in each cell
     colors: Vec<Option<Color>>,
+    /// Colors of each dot, 8 per cell, column by column
+    dot_colors: Vec<Option<Color>>,
+    /// Background color of each cell
+    backgrounds: Vec<Option<Color>>,
+    /// How a cell's color is picked from its dots' colors
+    policy: ColorPolicy,
+}
+
+/// What a cell shows: a character with foreground and background colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+    pub ch: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
 }
 
 impl BrailleGrid {
@@ -115,9 +155,23 @@ impl BrailleGrid {
             height,
             patterns: vec![0; size],
             colors: vec![None; size],
+            dot_colors: vec![None; size * 8],
+            backgrounds: vec![None; size],
+            policy: ColorPolicy::default(),
         }
     }
 
+    /// Pick cell colors from dot colors with `policy` (default: majority)
+    pub fn with_color_policy(mut self, policy: ColorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Get how cell colors are picked from dot colors
+    pub fn color_policy(&self) -> ColorPolicy {
+        self.policy
+    }
+
     /// Get width in terminal cells
     pub fn width(&self) -> usize {
         self.width
@@ -138,10 +192,35 @@ impl BrailleGrid {
         self.height * 4
     }
 
-    /// Clear all dots
+    /// Clear all dots and backgrounds
     pub fn clear(&mut self) {
         self.patterns.fill(0);
         self.colors.fill(None);
+        self.dot_colors.fill(None);
+        self.backgrounds.fill(None);
+    }
+
+    /// Set the background color of a cell
+    pub fn set_background(&mut self, cell_x: usize, cell_y: usize, color: Color) {
+        if cell_x >= self.width || cell_y >= self.height {
+            return;
+        }
+
+        self.backgrounds[cell_y * self.width + cell_x] = Some(color);
+    }
+
+    /// Set the background color of every cell
+    pub fn fill_background(&mut self, color: Color) {
+        self.backgrounds.fill(Some(color));
+    }
+
+    /// Get the background color of a cell
+    pub fn get_background(&self, cell_x: usize, cell_y: usize) -> Option<Color> {
+        if cell_x >= self.width || cell_y >= self.height {
+            return None;
+        }
+
+        self.backgrounds[cell_y * self.width + cell_x]
     }
 
     /// Set a single dot at the specified position
@@ -159,27 +238,14 @@ impl BrailleGrid {
         let cell_y = dot_y / 4;
         let cell_index = cell_y * self.width + cell_x;
 
-        // Determine which dot within the cell (0-7)
-        let local_x = dot_x % 2;
-        let local_y = dot_y % 4;
-
-        // Map to Braille dot position
-        let dot_bit = match (local_x, local_y) {
-            (0, 0) => BrailleDot::Dot1 as u8,
-            (0, 1) => BrailleDot::Dot2 as u8,
-            (0, 2) => BrailleDot::Dot3 as u8,
-            (0, 3) => BrailleDot::Dot7 as u8,
-            (1, 0) => BrailleDot::Dot4 as u8,
-            (1, 1) => BrailleDot::Dot5 as u8,
-            (1, 2) => BrailleDot::Dot6 as u8,
-            (1, 3) => BrailleDot::Dot8 as u8,
-            _ => unreachable!(),
-        };
-
-        self.patterns[cell_index] |= dot_bit;
+        // Map the dot within the cell to its Braille dot position
+        self.patterns[cell_index] |= DOT_BITS[dot_x % 2][dot_y % 4];
     }
 
     /// Set a dot with color
+    ///
+    /// Each dot keeps its own color; the cell is drawn in one picked by the
+    /// grid's [`ColorPolicy`].
     pub fn set_dot_with_color(&mut self, dot_x: usize, dot_y: usize, color: Color) {
         self.set_dot(dot_x, dot_y);
 
@@ -192,116 +258,7 @@ impl BrailleGrid {
         let cell_index = cell_y * self.width + cell_x;
 
         self.colors[cell_index] = Some(color);
-    }
-
-    /// Draw a line between two points using Bresenham's algorithm
-    ///
-    /// # Arguments
-    /// * `x0, y0` - Start point in dots
-    /// * `x1, y1` - End point in dots
-    pub fn draw_line(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) {
-        let dx = (x1 as i32 - x0 as i32).abs();
-        let dy = (y1 as i32 - y0 as i32).abs();
-        let sx = if x0 < x1 { 1i32 } else { -1i32 };
-        let sy = if y0 < y1 { 1i32 } else { -1i32 };
-        let mut err = dx - dy;
-
-        let mut x = x0 as i32;
-        let mut y = y0 as i32;
-
-        loop {
-            self.set_dot(x as usize, y as usize);
-
-            if x == x1 as i32 && y == y1 as i32 {
-                break;
-            }
-
-            let e2 = 2 * err;
-            if e2 > -dy {
-                err -= dy;
-                x += sx;
-            }
-            if e2 < dx {
-                err += dx;
-                y += sy;
-            }
-        }
-    }
-
-    /// Draw a line with color
-    pub fn draw_line_with_color(
-        &mut self,
-        x0: usize,
-        y0: usize,
-        x1: usize,
-        y1: usize,
-        color: Color,
-    ) {
-        let dx = (x1 as i32 - x0 as i32).abs();
-        let dy = (y1 as i32 - y0 as i32).abs();
-        let sx = if x0 < x1 { 1i32 } else { -1i32 };
-        let sy = if y0 < y1 { 1i32 } else { -1i32 };
-        let mut err = dx - dy;
-
-        let mut x = x0 as i32;
-        let mut y = y0 as i32;
-
-        loop {
-            self.set_dot_with_color(x as usize, y as usize, color);
-
-            if x == x1 as i32 && y == y1 as i32 {
-                break;
-            }
-
-            let e2 = 2 * err;
-            if e2 > -dy {
-                err -= dy;
-                x += sx;
-            }
-            if e2 < dx {
-                err += dx;
-                y += sy;
-            }
-        }
-    }
-
-    /// Draw a circle outline using the midpoint circle algorithm
-    pub fn draw_circle(&mut self, cx: usize, cy: usize, radius: usize, color: Color) {
-        if radius == 0 {
-            return;
-        }
-        let cx = cx as i32;
-        let cy = cy as i32;
-        let mut x = radius as i32;
-        let mut y = 0i32;
-        let mut err = 1 - x;
-        let max_x = self.dot_width() as i32 - 1;
-        let max_y = self.dot_height() as i32 - 1;
-
-        while x >= y {
-            let pts = [
-                (cx + x, cy + y),
-                (cx + y, cy + x),
-                (cx - y, cy + x),
-                (cx - x, cy + y),
-                (cx - x, cy - y),
-                (cx - y, cy - x),
-                (cx + y, cy - x),
-                (cx + x, cy - y),
-            ];
-            for (px, py) in pts {
-                if px >= 0 && py >= 0 && px <= max_x && py <= max_y {
-                    self.set_dot_with_color(px as usize, py as usize, color);
-                }
-            }
-            y += 1;
-            if err < 0 {
-                err += 2 * y + 1;
-            } else {
-                x -= 1;
-                err += 2 * (y - x) + 1;
-            }
-        }
+        self.dot_colors[cell_index * 8 + (dot_x % 2) * 4 + dot_y % 4] = Some(color);
     }
 
     /// Get the Braille character at a cell position
@@ -321,14 +278,87 @@ impl BrailleGrid {
         dots_to_char(self.patterns[index])
     }
 
-    /// Get the color at a cell position
+    /// Get the dot pattern at a cell position (bits as in [`BrailleDot`])
+    pub fn get_pattern(&self, cell_x: usize, cell_y: usize) -> u8 {
+        if cell_x >= self.width || cell_y >= self.height {
+            return 0;
+        }
+
+        self.patterns[cell_y * self.width + cell_x]
+    }
+
+    /// Get the character at a cell position in another encoding
+    pub fn get_char_as(&self, cell_x: usize, cell_y: usize, encoding: CellEncoding) -> char {
+        encoding.encode(self.get_pattern(cell_x, cell_y))
+    }
+
+    /// Get the color at a cell position, picked from its dots' colors
     pub fn get_color(&self, cell_x: usize, cell_y: usize) -> Option<Color> {
         if cell_x >= self.width || cell_y >= self.height {
             return None;
         }
 
         let index = cell_y * self.width + cell_x;
-        self.colors[index]
+        self.policy.resolve(self.dot_colors(index), self.colors[index])
+    }
+
+    /// Get what a cell shows in an encoding, with its colors
+    ///
+    /// Half blocks are drawn in two tones: the upper half in the foreground
+    /// color and the lower half in the background color, so each half keeps
+    /// its own dots' color.
+    pub fn get_glyph(&self, cell_x: usize, cell_y: usize, encoding: CellEncoding) -> Glyph {
+        let ch = self.get_char_as(cell_x, cell_y, encoding);
+        let fg = self.get_color(cell_x, cell_y);
+        let bg = self.get_background(cell_x, cell_y);
+        if encoding != CellEncoding::HalfBlock || ch != '█' {
+            return Glyph { ch, fg, bg };
+        }
+
+        // Dot colors are stored column by column: upper half first in each
+        let index = cell_y * self.width + cell_x;
+        let dots = self.dot_colors(index);
+        let last = self.colors[index];
+        let top = self.policy.resolve(&[dots[0], dots[1], dots[4], dots[5]], last);
+        let bottom = self.policy.resolve(&[dots[2], dots[3], dots[6], dots[7]], last);
+        match (top, bottom) {
+            (Some(top), Some(bottom)) if top != bottom => Glyph {
+                ch: '▀',
+                fg: Some(top),
+                bg: Some(bottom),
+            },
+            _ => Glyph { ch, fg, bg },
+        }
+    }
+
+    /// Get the color of a single dot
+    pub fn get_dot_color(&self, dot_x: usize, dot_y: usize) -> Option<Color> {
+        if dot_x >= self.dot_width() || dot_y >= self.dot_height() {
+            return None;
+        }
+
+        let index = (dot_y / 4) * self.width + dot_x / 2;
+        self.dot_colors(index)[(dot_x % 2) * 4 + dot_y % 4]
+    }
+
+    fn dot_colors(&self, cell_index: usize) -> &[Option<Color>] {
+        &self.dot_colors[cell_index * 8..cell_index * 8 + 8]
+    }
+
+    /// Set the color of every dot that is set
+    pub fn tint(&mut self, color: Color) {
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            if *pattern == 0 {
+                continue;
+            }
+            self.colors[index] = Some(color);
+            let bits = DOT_BITS.iter().flatten();
+            for (dot, bit) in self.dot_colors[index * 8..index * 8 + 8].iter_mut().zip(bits) {
+                if pattern & bit != 0 {
+                    *dot = Some(color);
+                }
+            }
+        }
     }
 
     /// Check if a cell has any dots set
@@ -342,6 +372,24 @@ impl BrailleGrid {
     }
 }
 
+/// Get a grid's colored dots as rows of `#` and `.`, for comparing in tests
+#[cfg(test)]
+pub(crate) fn dots(grid: &BrailleGrid) -> Vec<String> {
+    (0..grid.dot_height())
+        .map(|y| {
+            (0..grid.dot_width())
+                .map(|x| {
+                    if grid.get_dot_color(x, y).is_some() {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -399,4 +447,49 @@ mod tests {
         assert_eq!(grid.get_char(0, 0), '⠁');
         assert_eq!(grid.get_color(0, 0), Some(color));
     }
+
+    #[test]
+    fn test_dot_colors() {
+        let mut grid = BrailleGrid::new(1, 1);
+        grid.set_dot_with_color(0, 0, Color::RED);
+        grid.set_dot_with_color(1, 0, Color::RED);
+        grid.set_dot_with_color(0, 3, Color::BLUE);
+
+        assert_eq!(grid.get_dot_color(0, 3), Some(Color::BLUE));
+        assert_eq!(grid.get_dot_color(1, 1), None);
+        // The majority wins over the color drawn last
+        assert_eq!(grid.get_color(0, 0), Some(Color::RED));
+
+        let grid = grid.with_color_policy(ColorPolicy::Last);
+        assert_eq!(grid.get_color(0, 0), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn test_background_and_two_tone() {
+        let mut grid = BrailleGrid::new(2, 1);
+        grid.fill_background(Color::BLUE);
+        grid.set_dot_with_color(0, 0, Color::RED);
+        let glyph = grid.get_glyph(0, 0, CellEncoding::Braille);
+        assert_eq!((glyph.ch, glyph.fg, glyph.bg), ('⠁', Some(Color::RED), Some(Color::BLUE)));
+
+        // Red over green in one half-block cell
+        grid.set_dot_with_color(1, 3, Color::GREEN);
+        let glyph = grid.get_glyph(0, 0, CellEncoding::HalfBlock);
+        assert_eq!((glyph.ch, glyph.fg, glyph.bg), ('▀', Some(Color::RED), Some(Color::GREEN)));
+
+        grid.clear();
+        assert_eq!(grid.get_background(1, 0), None);
+    }
+
+    #[test]
+    fn test_tint() {
+        let mut grid = BrailleGrid::new(10, 10);
+        grid.set_dot(0, 0);
+        grid.set_dot_with_color(4, 0, Color::RED);
+        grid.tint(Color::BLUE);
+
+        assert_eq!(grid.get_color(0, 0), Some(Color::BLUE));
+        assert_eq!(grid.get_color(2, 0), Some(Color::BLUE));
+        assert_eq!(grid.get_color(1, 0), None);
+    }
 }
@@ -0,0 +1,108 @@
+// Picking a cell's color from the colors of its dots
+//
+// A terminal cell has one foreground color, but its eight dots may have been
+// drawn in different colors (two confetti particles sharing a cell).
+
+use super::Color;
+use anyhow::bail;
+use std::fmt;
+use std::str::FromStr;
+
+/// How a cell's color is picked from its dots' colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorPolicy {
+    /// The color most of the colored dots have
+    #[default]
+    Majority,
+    /// The brightest color
+    Brightest,
+    /// The average of the colors
+    Average,
+    /// The color drawn last
+    Last,
+}
+
+impl ColorPolicy {
+    /// Pick the color of a cell from the colors of its dots
+    ///
+    /// Uncolored dots don't count. `last` is the color drawn last.
+    pub(super) fn resolve(self, dots: &[Option<Color>], last: Option<Color>) -> Option<Color> {
+        let colors = || dots.iter().flatten().copied();
+
+        match self {
+            Self::Last => last,
+            // Ties go to the color first in dot order, so a still cell keeps
+            // its color from frame to frame
+            Self::Majority => colors().max_by_key(|color| {
+                let count = colors().filter(|other| other == color).count();
+                let first = dots.iter().position(|dot| *dot == Some(*color));
+                (count, std::cmp::Reverse(first))
+            }),
+            Self::Brightest => colors().max_by_key(|color| brightness(*color)),
+            Self::Average => {
+                let (mut r, mut g, mut b, mut count) = (0u32, 0u32, 0u32, 0u32);
+                for color in colors() {
+                    r += color.r as u32;
+                    g += color.g as u32;
+                    b += color.b as u32;
+                    count += 1;
+                }
+                (count > 0).then(|| Color::new((r / count) as u8, (g / count) as u8, (b / count) as u8))
+            }
+        }
+    }
+}
+
+/// Perceived brightness (Rec. 709 luma, scaled by 10000)
+fn brightness(color: Color) -> u32 {
+    2126 * color.r as u32 + 7152 * color.g as u32 + 722 * color.b as u32
+}
+
+impl fmt::Display for ColorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Majority => "majority",
+            Self::Brightest => "brightest",
+            Self::Average => "average",
+            Self::Last => "last",
+        })
+    }
+}
+
+impl FromStr for ColorPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "majority" => Ok(Self::Majority),
+            "brightest" => Ok(Self::Brightest),
+            "average" => Ok(Self::Average),
+            "last" => Ok(Self::Last),
+            _ => bail!(
+                "Unknown color policy '{}' (expected majority, brightest, average or last)",
+                s
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let dark = Some(Color::new(10, 10, 10));
+        let red = Some(Color::RED);
+        let dots = [red, None, dark, dark, None, None, None, None];
+
+        assert_eq!(ColorPolicy::Majority.resolve(&dots, red), dark);
+        assert_eq!(ColorPolicy::Brightest.resolve(&dots, dark), red);
+        assert_eq!(ColorPolicy::Average.resolve(&dots, red), Some(Color::new(91, 6, 6)));
+        assert_eq!(ColorPolicy::Last.resolve(&dots, red), red);
+
+        // A tie goes to the first dot's color
+        assert_eq!(ColorPolicy::Majority.resolve(&[dark, red], red), dark);
+        assert_eq!(ColorPolicy::Average.resolve(&[None; 8], None), None);
+    }
+}
@@ -0,0 +1,627 @@
+// Shape primitives on a BrailleGrid
+//
+// Coordinates here are signed dots, so shapes can start off the left or top
+// edge or reach past the grid (a rocket leaving the screen); whatever falls
+// outside is clipped. Angles are in radians, 0 pointing right and growing
+// clockwise, since y grows downwards.
+
+use super::{BrailleGrid, Color};
+use std::f64::consts::TAU;
+
+/// Segments a curve is flattened into per dot of its control polygon's length
+const CURVE_SEGMENTS_PER_DOT: f64 = 0.5;
+
+impl BrailleGrid {
+    /// Set a dot with color at signed coordinates, ignoring dots outside the
+    /// grid
+    pub fn plot(&mut self, x: i32, y: i32, color: Color) {
+        if x >= 0 && y >= 0 && (x as usize) < self.dot_width() && (y as usize) < self.dot_height() {
+            self.set_dot_with_color(x as usize, y as usize, color);
+        }
+    }
+
+    /// Draw a rectangle outline with its top left corner at `x, y`
+    pub fn draw_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (right, bottom) = (x.saturating_add(width - 1), y.saturating_add(height - 1));
+        self.fill_rect(x, y, width, 1, color);
+        self.fill_rect(x, bottom, width, 1, color);
+        self.fill_rect(x, y, 1, height, color);
+        self.fill_rect(right, y, 1, height, color);
+    }
+
+    /// Fill a rectangle with its top left corner at `x, y`
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        let (x0, y0) = (x.max(0), y.max(0));
+        let x1 = x.saturating_add(width).min(self.dot_width() as i32);
+        let y1 = y.saturating_add(height).min(self.dot_height() as i32);
+        for dot_y in y0..y1 {
+            for dot_x in x0..x1 {
+                self.set_dot_with_color(dot_x as usize, dot_y as usize, color);
+            }
+        }
+    }
+
+    /// Draw a rectangle outline with corners rounded to `radius`
+    pub fn draw_rounded_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        radius: i32,
+        color: Color,
+    ) {
+        let radius = radius.clamp(0, width.min(height).max(0) / 2);
+        if radius == 0 {
+            return self.draw_rect(x, y, width, height, color);
+        }
+        let (right, bottom) = (x.saturating_add(width - 1), y.saturating_add(height - 1));
+        let (left_center, top_center) = (x.saturating_add(radius), y.saturating_add(radius));
+        let straight_width = width - 2 * radius;
+        let straight_height = height - 2 * radius;
+        self.fill_rect(left_center, y, straight_width, 1, color);
+        self.fill_rect(left_center, bottom, straight_width, 1, color);
+        self.fill_rect(x, top_center, 1, straight_height, color);
+        self.fill_rect(right, top_center, 1, straight_height, color);
+
+        use std::f64::consts::{FRAC_PI_2, PI};
+        let corners = [
+            (left_center, top_center, PI),
+            (right - radius, top_center, -FRAC_PI_2),
+            (right - radius, bottom - radius, 0.0),
+            (left_center, bottom - radius, FRAC_PI_2),
+        ];
+        for (cx, cy, start) in corners {
+            self.draw_arc(cx, cy, radius, start, start + FRAC_PI_2, color);
+        }
+    }
+
+    /// Fill a rectangle with corners rounded to `radius`
+    pub fn fill_rounded_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        radius: i32,
+        color: Color,
+    ) {
+        let radius = radius.clamp(0, width.min(height).max(0) / 2) as i64;
+        let (x, y, width, height) = (x as i64, y as i64, width as i64, height as i64);
+        let (first, last) = self.visible_rows(y, y + height - 1);
+        for row in first - y..=last - y {
+            // How far into a corner's rows this row is, from the corner's center
+            let from_center = (radius - row).max(row - (height - 1 - radius)).max(0);
+            let inset = if from_center > 0 {
+                let r = radius as f64;
+                (r - (r * r - (from_center as f64 - 0.5).powi(2)).max(0.0).sqrt()).round() as i64
+            } else {
+                0
+            };
+            self.fill_span(x + inset, x + width - 1 - inset, y + row, color);
+        }
+    }
+
+    /// Draw a circle outline using the midpoint circle algorithm
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, color: Color) {
+        self.draw_arc(cx, cy, radius, 0.0, TAU, color);
+    }
+
+    /// Fill a circle
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, radius: i32, color: Color) {
+        self.fill_ellipse(cx, cy, radius, radius, color);
+    }
+
+    /// Draw an ellipse outline: the edge of what
+    /// [`fill_ellipse`](Self::fill_ellipse) covers
+    pub fn draw_ellipse(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, color: Color) {
+        if rx < 0 || ry < 0 {
+            return;
+        }
+        let (cx, cy) = (cx as i64, cy as i64);
+        let (first, last) = self.visible_rows(cy - ry as i64, cy + ry as i64);
+        for y in first..=last {
+            let dy = (y - cy).abs();
+            // Each row reaches in to where the row further out ends, so
+            // steep sides stay connected
+            let outer = ellipse_half_width(rx, ry, dy);
+            let inner = (ellipse_half_width(rx, ry, dy + 1) + 1).min(outer);
+            self.fill_span(cx + inner, cx + outer, y, color);
+            self.fill_span(cx - outer, cx - inner, y, color);
+        }
+    }
+
+    /// Fill an ellipse
+    pub fn fill_ellipse(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, color: Color) {
+        if rx < 0 || ry < 0 {
+            return;
+        }
+        let (cx, cy) = (cx as i64, cy as i64);
+        let (first, last) = self.visible_rows(cy - ry as i64, cy + ry as i64);
+        for y in first..=last {
+            let half = ellipse_half_width(rx, ry, (y - cy).abs());
+            self.fill_span(cx - half, cx + half, y, color);
+        }
+    }
+
+    /// Draw part of a circle from `start` to `end` (radians, clockwise)
+    ///
+    /// The dots are the ones [`draw_circle`](Self::draw_circle) would set in
+    /// that range of angles.
+    pub fn draw_arc(&mut self, cx: i32, cy: i32, radius: i32, start: f64, end: f64, color: Color) {
+        if radius < 0 || end < start {
+            return;
+        }
+        let sweep = end - start;
+        let (cx, cy) = (cx as i64, cy as i64);
+        let plot = |grid: &mut Self, dx: i64, dy: i64| {
+            let angle = (dy as f64).atan2(dx as f64);
+            if (angle - start).rem_euclid(TAU) <= sweep + 1e-9 {
+                grid.plot_wide(cx + dx, cy + dy, color);
+            }
+        };
+
+        // Midpoint circle, one octant mirrored into all eight. An octant step
+        // `y` puts dots `y` away from the center on one axis and the other,
+        // so only the steps where that is on the grid on some axis are walked.
+        let radius = radius as i64;
+        let (max_x, max_y) = (self.dot_width() as i64 - 1, self.dot_height() as i64 - 1);
+        for (first, last) in [offsets_on_grid(cx, max_x), offsets_on_grid(cy, max_y)] {
+            let mut y = first;
+            let mut x = midpoint_circle_x(radius, y);
+            let mut err = x * x - x + y * y + 2 * y + 1 - radius * radius;
+            while x >= y && y <= last {
+                for (dx, dy) in [
+                    (x, y),
+                    (y, x),
+                    (-y, x),
+                    (-x, y),
+                    (-x, -y),
+                    (-y, -x),
+                    (y, -x),
+                    (x, -y),
+                ] {
+                    plot(self, dx, dy);
+                }
+                y += 1;
+                if err < 0 {
+                    err += 2 * y + 1;
+                } else {
+                    x -= 1;
+                    err += 2 * (y - x) + 1;
+                }
+            }
+        }
+    }
+
+    /// Draw a polygon outline, closing it back to the first point
+    pub fn draw_polygon(&mut self, points: &[(i32, i32)], color: Color) {
+        for (i, &from) in points.iter().enumerate() {
+            self.segment(from, points[(i + 1) % points.len()], color);
+        }
+    }
+
+    /// Fill a polygon with scanlines (even-odd rule), outline included
+    pub fn fill_polygon(&mut self, points: &[(i32, i32)], color: Color) {
+        let corners: Vec<(f64, f64)> = points.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+        self.fill_polygon_f(&corners, color);
+        // Scanlines leave out dots on the lower and right edges
+        self.draw_polygon(points, color);
+    }
+
+    /// Fill a polygon with fractional corners, covering the dots whose
+    /// centers are inside
+    fn fill_polygon_f(&mut self, points: &[(f64, f64)], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        // Whole coordinates are dot centers, so each row is scanned at its y
+        let top = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let bottom = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        let first_row = (top.ceil() as i32).max(0);
+        let last_row = (bottom.floor() as i32).min(self.dot_height() as i32 - 1);
+
+        let mut crossings = Vec::new();
+        for row in first_row..=last_row {
+            let y = row as f64;
+            crossings.clear();
+            for (i, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(i + 1) % points.len()];
+                // Half-open, so a corner on the scanline counts once
+                if (y0 <= y) != (y1 <= y) {
+                    crossings.push(x0 + (y - y0) / (y1 - y0) * (x1 - x0));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                self.fill_span(span[0].ceil() as i64, span[1].floor() as i64, row as i64, color);
+            }
+        }
+    }
+
+    /// Draw a quadratic Bezier curve from `p0` to `p2`, bent towards `p1`
+    pub fn draw_quad_bezier(
+        &mut self,
+        p0: (i32, i32),
+        p1: (i32, i32),
+        p2: (i32, i32),
+        color: Color,
+    ) {
+        self.draw_curve(&[p0, p1, p2], color, |t, [a, b, c, _]| {
+            let u = 1.0 - t;
+            u * u * a + 2.0 * u * t * b + t * t * c
+        });
+    }
+
+    /// Draw a cubic Bezier curve from `p0` to `p3`, bent towards `p1` and `p2`
+    pub fn draw_cubic_bezier(
+        &mut self,
+        p0: (i32, i32),
+        p1: (i32, i32),
+        p2: (i32, i32),
+        p3: (i32, i32),
+        color: Color,
+    ) {
+        self.draw_curve(&[p0, p1, p2, p3], color, |t, [a, b, c, d]| {
+            let u = 1.0 - t;
+            u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+        });
+    }
+
+    /// Draw a curve as line segments, evaluating `at` on each axis of the
+    /// control points
+    fn draw_curve<F>(&mut self, control: &[(i32, i32)], color: Color, at: F)
+    where
+        F: Fn(f64, [f64; 4]) -> f64,
+    {
+        let axis = |pick: fn(&(i32, i32)) -> i32| {
+            let mut values = [0.0; 4];
+            for (value, point) in values.iter_mut().zip(control) {
+                *value = pick(point) as f64;
+            }
+            values
+        };
+        let (xs, ys) = (axis(|p| p.0), axis(|p| p.1));
+
+        let length: f64 = control
+            .windows(2)
+            .map(|pair| {
+                let dx = pair[1].0 as f64 - pair[0].0 as f64;
+                dx.hypot(pair[1].1 as f64 - pair[0].1 as f64)
+            })
+            .sum();
+        let steps = ((length * CURVE_SEGMENTS_PER_DOT).ceil() as usize).clamp(1, 4096);
+
+        let mut previous = control[0];
+        for i in 1..=steps {
+            let t = i as f64 / steps as f64;
+            let next = (at(t, xs).round() as i32, at(t, ys).round() as i32);
+            self.segment(previous, next, color);
+            previous = next;
+        }
+    }
+
+    /// Draw a line between two points using Bresenham's algorithm
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
+        if let Some((from, to)) = self.clip((x0, y0), (x1, y1)) {
+            // Clipped points are on the grid
+            self.bresenham(from, to, |grid, x, y| grid.set_dot(x as usize, y as usize));
+        }
+    }
+
+    /// Draw a line with color
+    pub fn draw_line_with_color(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+        self.segment((x0, y0), (x1, y1), color);
+    }
+
+    /// Draw a line `thickness` dots wide, with square ends
+    pub fn draw_thick_line(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        thickness: i32,
+        color: Color,
+    ) {
+        if thickness <= 1 || (x0, y0) == (x1, y1) {
+            if thickness >= 1 {
+                self.segment((x0, y0), (x1, y1), color);
+            }
+            return;
+        }
+        let (dx, dy) = (x1 as f64 - x0 as f64, y1 as f64 - y0 as f64);
+        let length = dx.hypot(dy);
+        // Half the thickness, across the line
+        let half = thickness as f64 / 2.0;
+        let (nx, ny) = (-dy / length * half, dx / length * half);
+        let (x0, y0, x1, y1) = (x0 as f64, y0 as f64, x1 as f64, y1 as f64);
+        self.fill_polygon_f(
+            &[
+                (x0 + nx, y0 + ny),
+                (x1 + nx, y1 + ny),
+                (x1 - nx, y1 - ny),
+                (x0 - nx, y0 - ny),
+            ],
+            color,
+        );
+    }
+
+    /// Draw a dashed line: with a `(dash, gap)` pattern, `dash` dots drawn,
+    /// then `gap` dots skipped
+    pub fn draw_dashed_line(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        (dash, gap): (u32, u32),
+        color: Color,
+    ) {
+        let period = (dash as i64 + gap as i64).max(1);
+        let Some(((cx0, cy0), end)) = self.clip((x0, y0), (x1, y1)) else {
+            return;
+        };
+        // Keep the pattern where it would be without clipping
+        let offset = (cx0 as i64 - x0 as i64).abs().max((cy0 as i64 - y0 as i64).abs());
+        let mut step = offset;
+        self.bresenham((cx0, cy0), end, |grid, x, y| {
+            if step % period < dash as i64 {
+                grid.plot(x, y, color);
+            }
+            step += 1;
+        });
+    }
+
+    /// Set a dot at wide coordinates, which may be far off the grid
+    fn plot_wide(&mut self, x: i64, y: i64, color: Color) {
+        if let (Ok(x), Ok(y)) = (i32::try_from(x), i32::try_from(y)) {
+            self.plot(x, y, color);
+        }
+    }
+
+    /// Fill dots `x0..=x1` of row `y`, clipped to the grid
+    fn fill_span(&mut self, x0: i64, x1: i64, y: i64, color: Color) {
+        if !(0..self.dot_height() as i64).contains(&y) {
+            return;
+        }
+        for x in x0.max(0)..=x1.min(self.dot_width() as i64 - 1) {
+            self.set_dot_with_color(x as usize, y as usize, color);
+        }
+    }
+
+    /// Clamp rows `top..=bottom` to the grid's, empty if none are on it
+    fn visible_rows(&self, top: i64, bottom: i64) -> (i64, i64) {
+        (top.max(0), bottom.min(self.dot_height() as i64 - 1))
+    }
+
+    /// Draw a line between signed points, clipped to the grid
+    fn segment(&mut self, from: (i32, i32), to: (i32, i32), color: Color) {
+        if let Some((from, to)) = self.clip(from, to) {
+            self.bresenham(from, to, |grid, x, y| grid.plot(x, y, color));
+        }
+    }
+
+    /// Walk the dots of a line with Bresenham's algorithm
+    fn bresenham<F>(&mut self, (x0, y0): (i32, i32), (x1, y1): (i32, i32), mut visit: F)
+    where
+        F: FnMut(&mut Self, i32, i32),
+    {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let (mut x, mut y) = (x0, y0);
+
+        loop {
+            visit(self, x, y);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Clip a line to the grid (Liang-Barsky), or `None` if it misses it
+    ///
+    /// Lines inside the grid are left alone, so they keep their exact dots.
+    fn clip(&self, from: (i32, i32), to: (i32, i32)) -> Option<((i32, i32), (i32, i32))> {
+        let (max_x, max_y) = (self.dot_width() as i32 - 1, self.dot_height() as i32 - 1);
+        let inside = |(x, y): (i32, i32)| (0..=max_x).contains(&x) && (0..=max_y).contains(&y);
+        if inside(from) && inside(to) {
+            return Some((from, to));
+        }
+        if max_x < 0 || max_y < 0 {
+            return None;
+        }
+
+        let (x0, y0) = (from.0 as f64, from.1 as f64);
+        let (dx, dy) = (to.0 as f64 - x0, to.1 as f64 - y0);
+        let (mut t0, mut t1) = (0.0f64, 1.0f64);
+        for (p, q) in [
+            (-dx, x0),
+            (dx, max_x as f64 - x0),
+            (-dy, y0),
+            (dy, max_y as f64 - y0),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+
+        let at = |t: f64| {
+            let x = (x0 + t * dx).round() as i32;
+            let y = (y0 + t * dy).round() as i32;
+            (x.clamp(0, max_x), y.clamp(0, max_y))
+        };
+        Some((at(t0), at(t1)))
+    }
+}
+
+/// Range of distances from `center` to the dots `0..=max` on one axis
+fn offsets_on_grid(center: i64, max: i64) -> (i64, i64) {
+    if center < 0 {
+        (-center, max - center)
+    } else if center > max {
+        (center - max, center)
+    } else {
+        (0, center.max(max - center))
+    }
+}
+
+/// The `x` the midpoint circle algorithm is at in step `y` of its octant:
+/// the largest with `x * (x - 1) < radius² - y²`
+fn midpoint_circle_x(radius: i64, y: i64) -> i64 {
+    let room = radius * radius - y * y;
+    let mut x = ((1.0 + (1.0 + 4.0 * room as f64).max(0.0).sqrt()) / 2.0) as i64;
+    while x > 0 && x * (x - 1) >= room {
+        x -= 1;
+    }
+    while (x + 1) * x < room {
+        x += 1;
+    }
+    x
+}
+
+/// Half the width of an ellipse's row `dy` dots from its center, or -1
+/// past its top and bottom
+fn ellipse_half_width(rx: i32, ry: i32, dy: i64) -> i64 {
+    if dy > ry as i64 {
+        return -1;
+    }
+    let t = if ry == 0 {
+        0.0
+    } else {
+        dy as f64 / (ry as f64 + 0.5)
+    };
+    ((rx as f64 + 0.5) * (1.0 - t * t).max(0.0).sqrt()).floor() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::braille::dots;
+
+    #[test]
+    fn test_fills_clip_at_edges() {
+        let mut grid = BrailleGrid::new(3, 1);
+        grid.fill_rect(-2, -2, 4, 4, Color::RED);
+        grid.fill_rect(5, 3, 100, 100, Color::RED);
+        assert_eq!(dots(&grid), ["##....", "##....", "......", ".....#"]);
+
+        let mut grid = BrailleGrid::new(3, 1);
+        grid.fill_polygon(&[(0, 0), (5, 0), (0, 3)], Color::RED);
+        assert_eq!(dots(&grid), ["######", "#####.", "###...", "#....."]);
+
+        let mut grid = BrailleGrid::new(3, 2);
+        grid.fill_circle(-1, 3, 2, Color::RED);
+        assert_eq!(
+            dots(&grid)[1..6],
+            ["#.....", "##....", "##....", "##....", "#....."]
+        );
+    }
+
+    #[test]
+    fn test_outlines() {
+        let mut grid = BrailleGrid::new(3, 2);
+        grid.draw_rect(1, 1, 4, 3, Color::RED);
+        assert_eq!(
+            dots(&grid)[..5],
+            ["......", ".####.", ".#..#.", ".####.", "......"]
+        );
+
+        let mut grid = BrailleGrid::new(4, 2);
+        grid.draw_rounded_rect(0, 0, 8, 8, 2, Color::RED);
+        assert_eq!(dots(&grid)[..3], [".######.", "#......#", "#......#"]);
+
+        // An ellipse is symmetric around its center
+        let mut grid = BrailleGrid::new(5, 3);
+        grid.draw_ellipse(4, 5, 4, 3, Color::RED);
+        let rows = dots(&grid);
+        assert_eq!(rows[2], rows[8]);
+        assert_eq!(rows[5], "#.......#.");
+    }
+
+    #[test]
+    fn test_extreme_extents() {
+        const MIN: i32 = i32::MIN;
+        const MAX: i32 = i32::MAX;
+
+        // Nothing overflows, and off-grid rows are never walked
+        let mut grid = BrailleGrid::new(3, 2);
+        grid.draw_rect(MIN, MIN, MAX, MAX, Color::RED);
+        grid.draw_rounded_rect(MIN, MIN, MAX, MAX, MAX, Color::RED);
+        grid.fill_rounded_rect(MAX, MAX, MAX, MAX, 3, Color::RED);
+        grid.draw_ellipse(MAX, MIN, MAX, MAX, Color::RED);
+        grid.draw_arc(MIN, MIN, MAX, 0.0, TAU, Color::RED);
+        grid.draw_circle(MAX, MAX, MAX, Color::RED);
+        grid.draw_line(MIN, MIN, MAX, MIN);
+        grid.draw_dashed_line(MIN, MIN, MAX, MIN, (1, 1), Color::RED);
+        grid.draw_dashed_line(MIN, MIN, MAX, MIN, (u32::MAX, u32::MAX), Color::RED);
+        assert_eq!(dots(&grid), ["......"; 8]);
+
+        let fills: [fn(&mut BrailleGrid); 3] = [
+            |grid| grid.fill_ellipse(0, 0, MAX, MAX, Color::RED),
+            |grid| grid.fill_rounded_rect(MIN / 2, MIN / 2, MAX, MAX, MAX, Color::RED),
+            |grid| grid.fill_polygon(&[(MIN, MIN), (MAX, MIN), (MAX, MAX), (MIN, MAX)], Color::RED),
+        ];
+        for fill in fills {
+            let mut grid = BrailleGrid::new(3, 2);
+            fill(&mut grid);
+            assert_eq!(dots(&grid), ["######"; 8]);
+        }
+
+        let mut grid = BrailleGrid::new(3, 2);
+        grid.draw_rect(0, 0, MAX, MAX, Color::RED);
+        grid.draw_dashed_line(MIN, 3, MAX, 3, (1, 1), Color::RED);
+        grid.draw_thick_line(MIN, 6, MAX, 6, 1, Color::RED);
+        grid.draw_line_with_color(MAX, 7, MIN, 7, Color::RED);
+        assert_eq!(
+            dots(&grid),
+            ["######", "#.....", "#.....", "#.#.#.", "#.....", "#.....", "######", "######"]
+        );
+    }
+
+    #[test]
+    fn test_lines_and_curves() {
+        let mut grid = BrailleGrid::new(5, 1);
+        grid.draw_dashed_line(-3, 0, 20, 0, (2, 1), Color::RED);
+        // The pattern starts at x = -3, off the grid
+        assert_eq!(dots(&grid)[0], "##.##.##.#");
+
+        let mut grid = BrailleGrid::new(5, 1);
+        grid.draw_thick_line(0, 1, 9, 1, 3, Color::RED);
+        assert_eq!(dots(&grid)[..3], ["##########"; 3]);
+
+        // Curves pass through their end points and stay on the grid
+        let mut grid = BrailleGrid::new(5, 3);
+        grid.draw_cubic_bezier((0, 11), (0, -20), (9, 30), (9, 0), Color::RED);
+        grid.draw_quad_bezier((-50, 0), (4, 40), (50, 0), Color::RED);
+        assert!(grid.get_dot_color(0, 11).is_some());
+        assert!(grid.get_dot_color(9, 0).is_some());
+    }
+}
@@ -0,0 +1,655 @@
+// Configuration file loading
+//
+// Animations per command are read from ~/.config/crabcrust/config.yaml and
+// an optional .crabcrust.yaml at the repository root, which overrides it:
+//
+//   render_mode: inline
+//   inline_height: 18
+//   exec_mode: streaming
+//   color_depth: 256
+//   cell_encoding: halfblock
+//   color_policy: majority
+//   commands:
+//     git:
+//       running: spinner
+//       failure: { animation: rocket, color: "#ff4040" }
+//       subcommands:
+//         push:
+//           success: { animation: fireworks, duration: 3s }
+
+use crate::animation::{Animation, AnimationParams, AnimationPlayer, AnimationRegistry, TintedAnimation};
+use crate::braille::{CellEncoding, Color, ColorPolicy};
+use crate::executor::ExecutionMode;
+use crate::rendering::ColorDepth;
+use serde::de::{self, value::MapAccessDeserializer, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Name of the per-repository override file
+pub const REPO_CONFIG_FILE: &str = ".crabcrust.yaml";
+
+/// Error loading or validating a configuration file
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("{}{}: {message}", path.display(), location_suffix(*location))]
+    Invalid {
+        path: PathBuf,
+        /// Line and column (1-based) of the offending value, if known
+        location: Option<(usize, usize)>,
+        message: String,
+    },
+}
+
+impl ConfigError {
+    /// Line (1-based) of the offending value, if known
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ConfigError::Invalid { location, .. } => location.map(|(line, _)| line),
+            ConfigError::Read { .. } => None,
+        }
+    }
+
+    fn invalid(path: &Path, err: serde_yaml::Error) -> Self {
+        let location = err.location().map(|l| (l.line(), l.column()));
+        let mut message = err.to_string();
+
+        // serde_yaml appends the position; it is already in the prefix
+        if let Some((line, column)) = location {
+            let suffix = format!(" at line {} column {}", line, column);
+            if let Some(stripped) = message.strip_suffix(&suffix) {
+                message = stripped.to_string();
+            }
+        }
+
+        ConfigError::Invalid {
+            path: path.to_path_buf(),
+            location,
+            message,
+        }
+    }
+}
+
+fn location_suffix(location: Option<(usize, usize)>) -> String {
+    match location {
+        Some((line, column)) => format!(":{}:{}", line, column),
+        None => String::new(),
+    }
+}
+
+/// Where animations are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderModeSetting {
+    /// Beneath the prompt, keeping terminal history
+    Inline,
+    /// On the alternate screen
+    Fullscreen,
+}
+
+/// Stage of a wrapped command an animation is shown for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Running,
+    Success,
+    Failure,
+}
+
+/// A named animation with optional overrides
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationSpec {
+    /// Name of an animation in the [`AnimationRegistry`]
+    pub name: String,
+    /// Length of the animation, overriding its default
+    pub duration: Option<Duration>,
+    /// Single color to draw the animation in
+    pub color: Option<Color>,
+    /// File to load frames from, for `frame-based`
+    pub path: Option<PathBuf>,
+}
+
+impl AnimationSpec {
+    /// Create a spec for the named animation with default duration and colors
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            duration: None,
+            color: None,
+            path: None,
+        }
+    }
+
+    /// Build the animation through the global registry
+    pub fn build(&self) -> anyhow::Result<Box<dyn Animation>> {
+        let params = AnimationParams {
+            duration: self.duration,
+            path: self.path.clone(),
+            ..Default::default()
+        };
+        let animation = AnimationRegistry::create_global(&self.name, &params)?;
+        Ok(match self.color {
+            Some(color) => Box::new(TintedAnimation::new(animation, color)),
+            None => animation,
+        })
+    }
+}
+
+/// Animations for one command, optionally refined per subcommand
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommandConfig {
+    pub render_mode: Option<RenderModeSetting>,
+    pub running: Option<AnimationSpec>,
+    pub success: Option<AnimationSpec>,
+    pub failure: Option<AnimationSpec>,
+    pub subcommands: BTreeMap<String, CommandConfig>,
+}
+
+impl CommandConfig {
+    /// Get the animation configured for an outcome
+    pub fn animation(&self, outcome: Outcome) -> Option<&AnimationSpec> {
+        match outcome {
+            Outcome::Running => self.running.as_ref(),
+            Outcome::Success => self.success.as_ref(),
+            Outcome::Failure => self.failure.as_ref(),
+        }
+    }
+
+    /// Apply settings from `other` on top of these
+    fn merge(&mut self, other: CommandConfig) {
+        self.render_mode = other.render_mode.or(self.render_mode);
+        self.running = other.running.or(self.running.take());
+        self.success = other.success.or(self.success.take());
+        self.failure = other.failure.or(self.failure.take());
+        for (name, subcommand) in other.subcommands {
+            self.subcommands.entry(name).or_default().merge(subcommand);
+        }
+    }
+}
+
+/// User configuration
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Default render mode for all commands
+    pub render_mode: Option<RenderModeSetting>,
+    /// Height in rows of the inline animation area (default: 1/3 of the terminal)
+    pub inline_height: Option<u16>,
+    /// How wrapped commands run relative to the animation
+    #[serde(deserialize_with = "parse_optional")]
+    pub exec_mode: Option<ExecutionMode>,
+    /// Colors the terminal can show, overriding detection (and `NO_COLOR`)
+    #[serde(deserialize_with = "parse_optional")]
+    pub color_depth: Option<ColorDepth>,
+    /// Characters cells are drawn with, for fonts with poor Braille
+    #[serde(deserialize_with = "parse_optional")]
+    pub cell_encoding: Option<CellEncoding>,
+    /// How a cell's color is picked when its dots have different colors
+    #[serde(deserialize_with = "parse_optional")]
+    pub color_policy: Option<ColorPolicy>,
+    /// Per-command animations, keyed by program name
+    pub commands: BTreeMap<String, CommandConfig>,
+}
+
+impl Config {
+    /// Parse a configuration document; `path` is only used in error messages
+    pub fn parse(source: &str, path: &Path) -> Result<Self, ConfigError> {
+        // An empty or comment-only file is a valid, empty configuration
+        let value: serde_yaml::Value =
+            serde_yaml::from_str(source).map_err(|e| ConfigError::invalid(path, e))?;
+        if value.is_null() {
+            return Ok(Self::default());
+        }
+
+        serde_yaml::from_str(source).map_err(|e| ConfigError::invalid(path, e))
+    }
+
+    /// Load and validate a configuration file
+    pub fn load_file(path: &Path) -> Result<Self, ConfigError> {
+        let source = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&source, path)
+    }
+
+    /// Load the user configuration, overridden by the current repository's
+    ///
+    /// Missing files are skipped; invalid ones are an error.
+    pub fn load() -> Result<Self, ConfigError> {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let paths = [Self::user_config_path(), Self::repo_config_path(&cwd)];
+
+        let mut config = Self::default();
+        for path in paths.into_iter().flatten() {
+            if path.is_file() {
+                config.merge(Self::load_file(&path)?);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Location of the user configuration file
+    ///
+    /// `$XDG_CONFIG_HOME/crabcrust/config.yaml`, falling back to
+    /// `~/.config/crabcrust/config.yaml`.
+    pub fn user_config_path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_home.join("crabcrust").join("config.yaml"))
+    }
+
+    /// Find the repository override file for a working directory
+    ///
+    /// Searches `start` and its parents up to the repository root (the first
+    /// directory containing `.git`).
+    pub fn repo_config_path(start: &Path) -> Option<PathBuf> {
+        for dir in start.ancestors() {
+            let candidate = dir.join(REPO_CONFIG_FILE);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Apply settings from `other` on top of these
+    pub fn merge(&mut self, other: Config) {
+        self.render_mode = other.render_mode.or(self.render_mode);
+        self.inline_height = other.inline_height.or(self.inline_height);
+        self.exec_mode = other.exec_mode.or(self.exec_mode);
+        self.color_depth = other.color_depth.or(self.color_depth);
+        self.cell_encoding = other.cell_encoding.or(self.cell_encoding);
+        self.color_policy = other.color_policy.or(self.color_policy);
+        for (name, command) in other.commands {
+            self.commands.entry(name).or_default().merge(command);
+        }
+    }
+
+    /// Apply the configured color depth, cell encoding and color policy to
+    /// a player
+    pub fn apply_to(&self, mut player: AnimationPlayer) -> AnimationPlayer {
+        if let Some(color_depth) = self.color_depth {
+            player = player.with_color_depth(color_depth);
+        }
+        if let Some(encoding) = self.cell_encoding {
+            player = player.with_cell_encoding(encoding);
+        }
+        if let Some(policy) = self.color_policy {
+            player = player.with_color_policy(policy);
+        }
+        player
+    }
+
+    /// Get the animation for a command outcome
+    ///
+    /// A subcommand's own setting wins over the command-wide one.
+    pub fn animation(
+        &self,
+        command: &str,
+        subcommand: Option<&str>,
+        outcome: Outcome,
+    ) -> Option<&AnimationSpec> {
+        let config = self.commands.get(command)?;
+        subcommand
+            .and_then(|name| config.subcommands.get(name))
+            .and_then(|sub| sub.animation(outcome))
+            .or_else(|| config.animation(outcome))
+    }
+
+    /// Set the animation for a command outcome, taking precedence over files
+    ///
+    /// With a subcommand, only that subcommand is affected.
+    pub fn set_animation(
+        &mut self,
+        command: &str,
+        subcommand: Option<&str>,
+        outcome: Outcome,
+        spec: AnimationSpec,
+    ) {
+        let mut config = self.commands.entry(command.to_string()).or_default();
+        if let Some(name) = subcommand {
+            config = config.subcommands.entry(name.to_string()).or_default();
+        }
+
+        let slot = match outcome {
+            Outcome::Running => &mut config.running,
+            Outcome::Success => &mut config.success,
+            Outcome::Failure => &mut config.failure,
+        };
+        *slot = Some(spec);
+    }
+
+    /// Get the render mode for a command, falling back to the global setting
+    pub fn render_mode(&self, command: &str, subcommand: Option<&str>) -> Option<RenderModeSetting> {
+        let config = self.commands.get(command);
+        config
+            .and_then(|c| subcommand.and_then(|name| c.subcommands.get(name)))
+            .and_then(|sub| sub.render_mode)
+            .or_else(|| config.and_then(|c| c.render_mode))
+            .or(self.render_mode)
+    }
+}
+
+/// Deserialize a string through `parse`
+///
+/// Errors are raised inside the visitor so serde_yaml reports the position
+/// of the string itself rather than of the enclosing map.
+fn deserialize_str_with<'de, D, T>(
+    deserializer: D,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ParseVisitor<T>(fn(&str) -> Result<T, String>);
+
+    impl<'de, T> Visitor<'de> for ParseVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a string")
+        }
+
+        fn visit_str<E: de::Error>(self, text: &str) -> Result<T, E> {
+            (self.0)(text).map_err(E::custom)
+        }
+
+        // Unquoted numbers, like `color_depth: 256`
+        fn visit_u64<E: de::Error>(self, number: u64) -> Result<T, E> {
+            self.visit_str(&number.to_string())
+        }
+    }
+
+    deserializer.deserialize_str(ParseVisitor(parse))
+}
+
+/// Deserialize an optional value through its `FromStr` impl
+fn parse_optional<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserialize_str_with(deserializer, |text| text.parse().map_err(|e: T::Err| e.to_string()))
+        .map(Some)
+}
+
+impl<'de> Deserialize<'de> for AnimationSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Long form: `{ animation: rocket, duration: 2s, color: "#ff0000" }`
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Detailed {
+            animation: AnimationName,
+            #[serde(default)]
+            duration: Option<ConfigDuration>,
+            #[serde(default)]
+            color: Option<ConfigColor>,
+            #[serde(default)]
+            path: Option<PathBuf>,
+        }
+
+        struct SpecVisitor;
+
+        impl<'de> Visitor<'de> for SpecVisitor {
+            type Value = AnimationSpec;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an animation name or a map with `animation`, `duration` and `color`")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                let name = validate_name(name).map_err(E::custom)?;
+                Ok(AnimationSpec::named(&name))
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let detailed = Detailed::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(AnimationSpec {
+                    name: detailed.animation.0,
+                    duration: detailed.duration.map(|d| d.0),
+                    color: detailed.color.map(|c| c.0),
+                    path: detailed.path,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(SpecVisitor)
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let names = AnimationRegistry::global_names();
+    if names.iter().any(|known| known == name) {
+        Ok(name.to_string())
+    } else {
+        Err(format!(
+            "unknown animation `{}`, expected one of: {}",
+            name,
+            names.join(", ")
+        ))
+    }
+}
+
+/// An animation name checked against the registry
+struct AnimationName(String);
+
+impl<'de> Deserialize<'de> for AnimationName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_str_with(deserializer, validate_name).map(AnimationName)
+    }
+}
+
+/// A duration given as seconds (`2`, `1.5`) or with a unit (`1500ms`, `2s`)
+struct ConfigDuration(Duration);
+
+impl<'de> Deserialize<'de> for ConfigDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DurationVisitor;
+
+        impl<'de> Visitor<'de> for DurationVisitor {
+            type Value = ConfigDuration;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a duration such as `2`, `1.5s` or `1500ms`")
+            }
+
+            fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Self::Value, E> {
+                Ok(ConfigDuration(Duration::from_secs(secs)))
+            }
+
+            fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Self::Value, E> {
+                u64::try_from(secs)
+                    .map_err(|_| E::custom("duration must not be negative"))
+                    .and_then(|secs| self.visit_u64(secs))
+            }
+
+            fn visit_f64<E: de::Error>(self, secs: f64) -> Result<Self::Value, E> {
+                Duration::try_from_secs_f64(secs)
+                    .map(ConfigDuration)
+                    .map_err(|_| E::custom("duration must be a non-negative number of seconds"))
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+                let text = text.trim();
+                let (number, scale) = if let Some(ms) = text.strip_suffix("ms") {
+                    (ms, 0.001)
+                } else if let Some(secs) = text.strip_suffix('s') {
+                    (secs, 1.0)
+                } else {
+                    (text, 1.0)
+                };
+
+                let value: f64 = number
+                    .trim()
+                    .parse()
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(text), &self))?;
+                self.visit_f64(value * scale)
+            }
+        }
+
+        deserializer.deserialize_any(DurationVisitor)
+    }
+}
+
+/// A color given as `#rrggbb` or a basic color name
+struct ConfigColor(Color);
+
+impl<'de> Deserialize<'de> for ConfigColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_str_with(deserializer, |text| {
+            parse_color(text)
+                .ok_or_else(|| format!("invalid color `{}`, expected `#rrggbb` or a color name", text))
+        })
+        .map(ConfigColor)
+    }
+}
+
+/// Parse `#rrggbb` or one of the named [`Color`] constants
+fn parse_color(text: &str) -> Option<Color> {
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::new(channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    match text.to_ascii_lowercase().as_str() {
+        "white" => Some(Color::WHITE),
+        "black" => Some(Color::BLACK),
+        "red" => Some(Color::RED),
+        "green" => Some(Color::GREEN),
+        "blue" => Some(Color::BLUE),
+        "yellow" => Some(Color::YELLOW),
+        "cyan" => Some(Color::CYAN),
+        "magenta" => Some(Color::MAGENTA),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<Config, ConfigError> {
+        Config::parse(source, Path::new("config.yaml"))
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let config = parse(
+            "render_mode: fullscreen\n\
+             exec_mode: pty\n\
+             color_depth: 256\n\
+             cell_encoding: sextant\n\
+             color_policy: brightest\n\
+             commands:\n\
+             \x20 git:\n\
+             \x20   running: spinner\n\
+             \x20   subcommands:\n\
+             \x20     push:\n\
+             \x20       success: { animation: rocket, duration: 1500ms, color: \"#ff8000\" }\n",
+        )
+        .expect("Config should parse");
+
+        assert_eq!(config.render_mode, Some(RenderModeSetting::Fullscreen));
+        assert_eq!(config.exec_mode, Some(ExecutionMode::Pty));
+        assert_eq!(config.color_depth, Some(ColorDepth::Ansi256));
+        assert_eq!(config.cell_encoding, Some(CellEncoding::Sextant));
+        assert_eq!(config.color_policy, Some(ColorPolicy::Brightest));
+
+        let push = config
+            .animation("git", Some("push"), Outcome::Success)
+            .expect("push success animation");
+        assert_eq!(push.name, "rocket");
+        assert_eq!(push.duration, Some(Duration::from_millis(1500)));
+        assert_eq!(push.color, Some(Color::new(255, 128, 0)));
+
+        // Subcommands fall back to the command-wide setting
+        let running = config.animation("git", Some("push"), Outcome::Running);
+        assert_eq!(running, Some(&AnimationSpec::named("spinner")));
+        assert!(config.animation("git", Some("push"), Outcome::Failure).is_none());
+    }
+
+    #[test]
+    fn test_empty_config() {
+        assert_eq!(parse("").unwrap(), Config::default());
+        assert_eq!(parse("# nothing yet\n").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_error_points_to_line() {
+        let err = parse("commands:\n  git:\n    success: lazer\n").unwrap_err();
+
+        assert_eq!(err.line(), Some(3));
+        let message = err.to_string();
+        assert!(message.starts_with("config.yaml:3:"), "{}", message);
+        assert!(message.contains("unknown animation `lazer`"), "{}", message);
+    }
+
+    #[test]
+    fn test_unknown_field_rejected() {
+        let err = parse("commands:\n  git:\n    sucess: rocket\n").unwrap_err();
+        assert_eq!(err.line(), Some(3));
+        assert!(err.to_string().contains("sucess"));
+    }
+
+    #[test]
+    fn test_merge_overrides_per_outcome() {
+        let mut config = parse(
+            "inline_height: 20\n\
+             commands:\n\
+             \x20 git:\n\
+             \x20   success: confetti\n\
+             \x20   failure: rocket\n",
+        )
+        .unwrap();
+        let repo = parse("commands:\n  git:\n    success: trophy\n").unwrap();
+        config.merge(repo);
+
+        assert_eq!(config.inline_height, Some(20));
+        assert_eq!(config.animation("git", None, Outcome::Success).unwrap().name, "trophy");
+        assert_eq!(config.animation("git", None, Outcome::Failure).unwrap().name, "rocket");
+    }
+
+    #[test]
+    fn test_set_animation_wins_for_subcommand() {
+        let mut config = parse(
+            "commands:\n  make:\n    subcommands:\n      deploy:\n        success: confetti\n",
+        )
+        .unwrap();
+        config.set_animation("make", Some("deploy"), Outcome::Success, AnimationSpec::named("rocket"));
+        config.set_animation("make", None, Outcome::Failure, AnimationSpec::named("spinner"));
+
+        let success = config.animation("make", Some("deploy"), Outcome::Success).unwrap();
+        assert_eq!(success.name, "rocket");
+        let failure = config.animation("make", Some("deploy"), Outcome::Failure).unwrap();
+        assert_eq!(failure.name, "spinner");
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#00ff7f"), Some(Color::new(0, 255, 127)));
+        assert_eq!(parse_color("Cyan"), Some(Color::CYAN));
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("mauve"), None);
+    }
+}
@@ -1,33 +1,55 @@
 // Command executor module
 
-use anyhow::{Context, Result};
-use std::process::{Command, Output, Stdio};
+mod paged;
+mod pty;
+mod stream;
+
+pub use paged::PagedCommand;
+pub use stream::{LineSplitter, OutputLine, OutputStream};
+
+use crate::signals;
+use anyhow::{bail, Context, Result};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Exit code reported when a command is killed for exceeding its timeout
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// How long to keep reading output after a timed-out command was killed
+///
+/// A grandchild can hold the pipes open after the child dies, so reading is
+/// abandoned after this grace period rather than waiting for EOF.
+const TIMEOUT_DRAIN_GRACE: Duration = Duration::from_millis(200);
 
 /// Result of command execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CommandResult {
     pub stdout: String,
     pub stderr: String,
+    /// Exit code, 128 + signal number if killed by a signal, or
+    /// [`TIMEOUT_EXIT_CODE`] if killed by the timeout
     pub exit_code: i32,
+    /// Whether the exit code counts as success (see [`CommandExecutor::with_success_codes`])
     pub success: bool,
+    /// Signal that terminated the command, if any
+    pub signal: Option<i32>,
+    /// The command was killed because it exceeded its timeout
+    pub timed_out: bool,
+    /// Wall-clock time from spawn to exit
+    pub elapsed: Duration,
+    /// Output lines in arrival order (only populated by streaming execution)
+    pub lines: Vec<OutputLine>,
+    /// Output was already shown on the terminal while the command ran (PTY
+    /// execution), or stdout was passed on by [`PagedCommand::pipe_to`]
+    pub echoed: bool,
 }
 
 impl CommandResult {
-    /// Create from std::process::Output
-    fn from_output(output: Output) -> Self {
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let exit_code = output.status.code().unwrap_or(-1);
-        let success = output.status.success();
-
-        Self {
-            stdout,
-            stderr,
-            exit_code,
-            success,
-        }
-    }
-
     /// Get combined output (stdout + stderr)
     pub fn combined_output(&self) -> String {
         let mut result = String::new();
@@ -42,13 +64,226 @@ impl CommandResult {
         }
         result
     }
+
+    /// Print captured output to the terminal
+    ///
+    /// Streamed results are replayed line by line to their original stream,
+    /// preserving interleaving; buffered results print [`combined_output`](Self::combined_output).
+    /// Does nothing if the output was already echoed live.
+    pub fn print_output(&self) {
+        if self.echoed {
+            return;
+        }
+
+        if self.lines.is_empty() {
+            println!("{}", self.combined_output());
+            return;
+        }
+
+        for line in &self.lines {
+            let terminator = line.terminator.map(String::from).unwrap_or_default();
+            match line.stream {
+                OutputStream::Stdout => print!("{}{}", line.text, terminator),
+                OutputStream::Stderr => eprint!("{}{}", line.text, terminator),
+            }
+        }
+    }
+}
+
+/// Output being read from a child pipe on a background thread
+struct PipeReader {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PipeReader {
+    /// Get everything read so far, waiting for EOF unless the command timed out
+    fn collect(self, timed_out: bool) -> Vec<u8> {
+        if let Some(thread) = self.thread {
+            if timed_out {
+                let deadline = Instant::now() + TIMEOUT_DRAIN_GRACE;
+                while !thread.is_finished() && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            } else {
+                let _ = thread.join();
+            }
+        }
+
+        let buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.clone()
+    }
+}
+
+/// Read a child pipe to EOF on a background thread
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> PipeReader {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let thread = pipe.map(|mut pipe| {
+        let buffer = buffer.clone();
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = pipe.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                buffer
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .extend_from_slice(&chunk[..n]);
+            }
+        })
+    });
+
+    PipeReader { buffer, thread }
+}
+
+/// Reassemble the raw bytes of one stream from streamed lines
+fn transcript_bytes(lines: &[OutputLine], stream: OutputStream) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for line in lines.iter().filter(|l| l.stream == stream) {
+        bytes.extend_from_slice(line.text.as_bytes());
+        if let Some(terminator) = line.terminator {
+            bytes.push(terminator as u8);
+        }
+    }
+    bytes
+}
+
+/// Exit code shells use when a command can't be found
+pub const NOT_FOUND_EXIT_CODE: i32 = 127;
+
+/// Look up a program the way a shell would
+///
+/// Names containing a path separator are used as given; anything else is
+/// searched for in the directories on `PATH`.
+pub fn find_program(name: &str) -> Option<PathBuf> {
+    if name.is_empty() {
+        return None;
+    }
+
+    if Path::new(name).components().count() > 1 {
+        let path = PathBuf::from(name);
+        return is_executable(&path).then_some(path);
+    }
+
+    let search_path = std::env::var_os("PATH")?;
+    std::env::split_paths(&search_path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Check if a path is a file we may execute
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+/// Map an exit status to a shell-style exit code and the terminating signal
+pub(crate) fn exit_code_of(status: ExitStatus) -> (i32, Option<i32>) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return (128 + signal, Some(signal));
+        }
+    }
+
+    (status.code().unwrap_or(-1), None)
+}
+
+/// How a wrapped command is executed relative to its animations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionMode {
+    /// Run the command to completion before animating (no live feedback)
+    Blocking,
+    /// Spawn the command in the background and animate until it exits
+    Concurrent,
+    /// Like `Concurrent`, but also show output live beneath the animation
+    #[default]
+    Streaming,
+    /// Run on a pseudo-terminal with full interactivity, animating afterwards (Linux only)
+    Pty,
+}
+
+impl std::str::FromStr for ExecutionMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "blocking" => Ok(Self::Blocking),
+            "concurrent" => Ok(Self::Concurrent),
+            "streaming" => Ok(Self::Streaming),
+            "pty" => Ok(Self::Pty),
+            _ => bail!(
+                "Unknown execution mode '{}' (expected blocking, concurrent, streaming or pty)",
+                s
+            ),
+        }
+    }
+}
+
+/// Handle to a command running in the background
+///
+/// Created by [`CommandExecutor::spawn`]. Poll [`RunningCommand::is_finished`]
+/// from a render loop, then call [`RunningCommand::wait`] to collect the result.
+pub struct RunningCommand {
+    runtime: tokio::runtime::Runtime,
+    handle: tokio::task::JoinHandle<Result<CommandResult>>,
+    output: Option<Receiver<OutputLine>>,
+}
+
+impl RunningCommand {
+    /// Take all output lines received since the last call (never blocks)
+    ///
+    /// Always empty unless the command was started with
+    /// [`CommandExecutor::spawn_streaming`].
+    pub fn drain_output(&self) -> Vec<OutputLine> {
+        self.output
+            .as_ref()
+            .map(|rx| rx.try_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Check whether the command has exited (never blocks)
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Wait for the command to exit and return its result
+    pub fn wait(self) -> Result<CommandResult> {
+        self.runtime
+            .block_on(self.handle)
+            .context("Failed to join async task")?
+    }
+}
+
+/// A change to the environment inherited by the command
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EnvChange {
+    Set(String, String),
+    Remove(String),
+    Clear,
 }
 
 /// Command executor for running CLI tools
+#[derive(Debug, Clone)]
 pub struct CommandExecutor {
     program: String,
     args: Vec<String>,
     cwd: Option<String>,
+    inherit_stdin: bool,
+    env: Vec<EnvChange>,
+    timeout: Option<Duration>,
+    success_codes: Option<Vec<i32>>,
 }
 
 impl CommandExecutor {
@@ -69,6 +304,10 @@ impl CommandExecutor {
             program: program.to_string(),
             args: args.iter().map(|s| s.to_string()).collect(),
             cwd: None,
+            inherit_stdin: true,
+            env: Vec::new(),
+            timeout: None,
+            success_codes: None,
         }
     }
 
@@ -78,8 +317,59 @@ impl CommandExecutor {
         self
     }
 
-    /// Execute the command and return the result
-    pub fn run(&self) -> Result<CommandResult> {
+    /// Pass our stdin through to the command (default), or give it an empty stdin
+    pub fn with_stdin(mut self, inherit: bool) -> Self {
+        self.inherit_stdin = inherit;
+        self
+    }
+
+    /// Set an environment variable for the command
+    pub fn with_env(mut self, key: &str, value: &str) -> Self {
+        self.env.push(EnvChange::Set(key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Remove an environment variable from the command's environment
+    pub fn without_env(mut self, key: &str) -> Self {
+        self.env.push(EnvChange::Remove(key.to_string()));
+        self
+    }
+
+    /// Start the command with an empty environment
+    ///
+    /// Variables added with [`with_env`](Self::with_env) afterwards are kept;
+    /// changes are applied in the order they were made.
+    pub fn with_clear_env(mut self) -> Self {
+        self.env.push(EnvChange::Clear);
+        self
+    }
+
+    /// Kill the command if it runs longer than `timeout`
+    ///
+    /// A timed-out command reports `timed_out`, is never successful, and
+    /// exits with [`TIMEOUT_EXIT_CODE`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Declare which exit codes count as success (default: only 0)
+    ///
+    /// # Example
+    /// ```
+    /// use crabcrust::executor::CommandExecutor;
+    ///
+    /// // `git diff --exit-code` returns 1 when there are differences
+    /// let executor = CommandExecutor::new("git", &["diff", "--exit-code"])
+    ///     .with_success_codes(&[0, 1]);
+    /// ```
+    pub fn with_success_codes(mut self, codes: &[i32]) -> Self {
+        self.success_codes = Some(codes.to_vec());
+        self
+    }
+
+    /// Build the process command with arguments, directory, stdin and environment
+    fn command(&self) -> Command {
         let mut cmd = Command::new(&self.program);
         cmd.args(&self.args);
 
@@ -87,42 +377,294 @@ impl CommandExecutor {
             cmd.current_dir(cwd);
         }
 
+        for change in &self.env {
+            match change {
+                EnvChange::Set(key, value) => cmd.env(key, value),
+                EnvChange::Remove(key) => cmd.env_remove(key),
+                EnvChange::Clear => cmd.env_clear(),
+            };
+        }
+
+        cmd.stdin(if self.inherit_stdin {
+            Stdio::inherit()
+        } else {
+            Stdio::null()
+        });
+
+        cmd
+    }
+
+    /// Check whether a command started at `started` has run out of time
+    fn timed_out(&self, started: Instant) -> bool {
+        self.timeout.is_some_and(|timeout| started.elapsed() >= timeout)
+    }
+
+    /// Build the result for a finished command, applying timeout and success codes
+    fn finish(
+        &self,
+        status: ExitStatus,
+        stdout: &[u8],
+        stderr: &[u8],
+        started: Instant,
+        timed_out: bool,
+    ) -> CommandResult {
+        let (exit_code, signal) = exit_code_of(status);
+        let exit_code = if timed_out { TIMEOUT_EXIT_CODE } else { exit_code };
+        let success = !timed_out
+            && signal.is_none()
+            && match &self.success_codes {
+                Some(codes) => codes.contains(&exit_code),
+                None => status.success(),
+            };
+
+        CommandResult {
+            stdout: String::from_utf8_lossy(stdout).to_string(),
+            stderr: String::from_utf8_lossy(stderr).to_string(),
+            exit_code,
+            success,
+            signal,
+            timed_out,
+            elapsed: started.elapsed(),
+            ..Default::default()
+        }
+    }
+
+    /// Wait for a child, killing it if the timeout expires
+    ///
+    /// Returns the exit status and whether the timeout fired.
+    fn wait_with_timeout(&self, child: &mut Child, started: Instant) -> Result<(ExitStatus, bool)> {
+        if self.timeout.is_none() {
+            return Ok((child.wait().context("Failed to wait for command")?, false));
+        }
+
+        loop {
+            if let Some(status) = child.try_wait().context("Failed to wait for command")? {
+                return Ok((status, false));
+            }
+            if self.timed_out(started) {
+                let _ = child.kill();
+                return Ok((child.wait().context("Failed to wait for command")?, true));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Execute the command and return the result
+    pub fn run(&self) -> Result<CommandResult> {
+        let mut cmd = self.command();
+
         // Capture stdout and stderr
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let output = cmd
-            .output()
+        let started = Instant::now();
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to execute command: {} {:?}", self.program, self.args))?;
+
+        // Pass termination signals on while we wait
+        let signals = signals::forward_to_child(child.id(), None)?;
+
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+        let (status, timed_out) = self.wait_with_timeout(&mut child, started)?;
+        signals.child_exited(&status);
+        let stdout = stdout.collect(timed_out);
+        let stderr = stderr.collect(timed_out);
+
+        Ok(self.finish(status, &stdout, &stderr, started, timed_out))
+    }
+
+    /// Execute the command on our own stdout and stderr, capturing nothing
+    ///
+    /// For plain passthrough: the output is exactly the command's own. The
+    /// result has `echoed` set and empty `stdout` and `stderr`.
+    pub fn run_inherited(&self) -> Result<CommandResult> {
+        let started = Instant::now();
+        let mut child = self
+            .command()
+            .spawn()
             .with_context(|| format!("Failed to execute command: {} {:?}", self.program, self.args))?;
 
-        Ok(CommandResult::from_output(output))
+        // Pass termination signals on while we wait
+        let signals = signals::forward_to_child(child.id(), None)?;
+
+        let (status, timed_out) = self.wait_with_timeout(&mut child, started)?;
+        signals.child_exited(&status);
+        Ok(CommandResult {
+            echoed: true,
+            ..self.finish(status, &[], &[], started, timed_out)
+        })
     }
 
     /// Execute the command asynchronously
     pub async fn run_async(&self) -> Result<CommandResult> {
-        let program = self.program.clone();
-        let args = self.args.clone();
-        let cwd = self.cwd.clone();
+        let executor = self.clone();
 
-        tokio::task::spawn_blocking(move || {
-            let mut cmd = Command::new(&program);
-            cmd.args(&args);
+        tokio::task::spawn_blocking(move || executor.run())
+            .await
+            .context("Failed to join async task")?
+    }
 
-            if let Some(cwd) = cwd {
-                cmd.current_dir(cwd);
-            }
+    /// Execute the command attached to a pseudo-terminal (Linux only)
+    ///
+    /// The command keeps colors, pagers, editors and prompts because it sees a
+    /// real terminal. Its output is shown live and also captured in `stdout`
+    /// (stderr is merged into it by the terminal), with `echoed` set.
+    pub fn run_pty(&self) -> Result<CommandResult> {
+        pty::run(self)
+    }
 
-            cmd.stdout(Stdio::piped());
-            cmd.stderr(Stdio::piped());
+    /// Spawn the command in the background and return immediately
+    ///
+    /// The command runs on its own tokio runtime via [`run_async`](Self::run_async),
+    /// so the calling thread stays free to drive an animation.
+    ///
+    /// # Example
+    /// ```
+    /// use crabcrust::executor::CommandExecutor;
+    ///
+    /// let running = CommandExecutor::new("echo", &["hi"]).spawn().unwrap();
+    /// while !running.is_finished() {
+    ///     // render a frame...
+    ///     std::thread::sleep(std::time::Duration::from_millis(10));
+    /// }
+    /// assert!(running.wait().unwrap().success);
+    /// ```
+    pub fn spawn(&self) -> Result<RunningCommand> {
+        let runtime = Self::background_runtime()?;
 
-            let output = cmd
-                .output()
-                .with_context(|| format!("Failed to execute command: {} {:?}", program, args))?;
+        let executor = self.clone();
+        let handle = runtime.spawn(async move { executor.run_async().await });
+
+        Ok(RunningCommand {
+            runtime,
+            handle,
+            output: None,
+        })
+    }
+
+    /// Spawn the command, reading only the first `max_lines` lines of stdout
+    ///
+    /// For output that may be huge, like `git log`: see [`PagedCommand`].
+    pub fn spawn_paged(&self, max_lines: usize) -> Result<PagedCommand> {
+        paged::spawn(self, max_lines)
+    }
+
+    /// Spawn the command in the background, streaming its output line by line
+    ///
+    /// Lines from stdout and stderr are available through
+    /// [`RunningCommand::drain_output`] as soon as they are written, and the
+    /// final [`CommandResult`] keeps them in the same order in `lines`.
+    pub fn spawn_streaming(&self) -> Result<RunningCommand> {
+        let runtime = Self::background_runtime()?;
+        let (tx, rx) = mpsc::channel();
+
+        let executor = self.clone();
+        let handle = runtime.spawn(async move { executor.run_streaming(tx).await });
+
+        Ok(RunningCommand {
+            runtime,
+            handle,
+            output: Some(rx),
+        })
+    }
+
+    /// Run the command, forwarding output lines to `tx` as they arrive
+    async fn run_streaming(&self, tx: Sender<OutputLine>) -> Result<CommandResult> {
+        let mut cmd = tokio::process::Command::from(self.command());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+
+        let started = Instant::now();
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to execute command: {} {:?}", self.program, self.args))?;
+
+        // Pass termination signals on while we wait
+        let signals = match child.id() {
+            Some(pid) => Some(signals::forward_to_child(pid, None)?),
+            None => None,
+        };
+
+        let transcript = Arc::new(Mutex::new(Vec::new()));
+        let stdout = child.stdout.take().context("Failed to capture stdout")?;
+        let stderr = child.stderr.take().context("Failed to capture stderr")?;
+        let stdout_task = tokio::spawn(stream::forward(
+            stdout,
+            OutputStream::Stdout,
+            tx.clone(),
+            transcript.clone(),
+        ));
+        let stderr_task = tokio::spawn(stream::forward(
+            stderr,
+            OutputStream::Stderr,
+            tx,
+            transcript.clone(),
+        ));
+
+        let (status, timed_out) = match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, child.wait()).await {
+                Ok(status) => (status, false),
+                Err(_) => {
+                    let _ = child.kill().await;
+                    (child.wait().await, true)
+                }
+            },
+            None => (child.wait().await, false),
+        };
+        let status = status.context("Failed to wait for command")?;
+        if let Some(signals) = &signals {
+            signals.child_exited(&status);
+        }
+
+        // After a timeout, don't wait forever on pipes held open by grandchildren
+        let grace = if timed_out {
+            TIMEOUT_DRAIN_GRACE
+        } else {
+            Duration::MAX
+        };
+        let stdout = tokio::time::timeout(grace, stdout_task).await;
+        let stderr = tokio::time::timeout(grace, stderr_task).await;
+
+        let lines = std::mem::take(&mut *transcript.lock().unwrap_or_else(|e| e.into_inner()));
+        type ReaderOutcome = std::result::Result<
+            std::result::Result<std::io::Result<Vec<u8>>, tokio::task::JoinError>,
+            tokio::time::error::Elapsed,
+        >;
+        let raw_output = |read: ReaderOutcome, stream| -> Result<Vec<u8>> {
+            match read {
+                Ok(joined) => Ok(joined.context("Failed to join output reader")??),
+                // Reader abandoned: rebuild what it saw from the transcript
+                Err(_) => Ok(transcript_bytes(&lines, stream)),
+            }
+        };
+        let stdout = raw_output(stdout, OutputStream::Stdout)?;
+        let stderr = raw_output(stderr, OutputStream::Stderr)?;
 
-            Ok(CommandResult::from_output(output))
+        Ok(CommandResult {
+            lines,
+            ..self.finish(status, &stdout, &stderr, started, timed_out)
         })
-        .await
-        .context("Failed to join async task")?
+    }
+
+    /// Build the runtime that drives a spawned command
+    fn background_runtime() -> Result<tokio::runtime::Runtime> {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .context("Failed to start async runtime")
+    }
+
+    /// Get the program being run
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Get the program's arguments
+    pub fn args(&self) -> &[String] {
+        &self.args
     }
 
     /// Get the command as a string for display
@@ -152,6 +694,145 @@ mod tests {
         assert_eq!(result.exit_code, 0);
     }
 
+    #[test]
+    fn test_spawned_command() {
+        let executor = CommandExecutor::new("echo", &["spawned"]);
+        let running = executor.spawn().expect("Failed to spawn echo");
+        let result = running.wait().expect("Failed to wait for echo");
+
+        assert!(result.success);
+        assert!(result.stdout.contains("spawned"));
+    }
+
+    #[test]
+    fn test_streaming_preserves_stream_identity() {
+        let executor = CommandExecutor::new("sh", &["-c", "echo out; echo err >&2"]);
+        let running = executor.spawn_streaming().expect("Failed to spawn sh");
+        let result = running.wait().expect("Failed to wait for sh");
+
+        assert!(result.success);
+        assert_eq!(result.stdout, "out\n");
+        assert_eq!(result.stderr, "err\n");
+        assert_eq!(result.lines.len(), 2);
+        assert!(result
+            .lines
+            .iter()
+            .any(|l| l.stream == OutputStream::Stdout && l.text == "out"));
+        assert!(result
+            .lines
+            .iter()
+            .any(|l| l.stream == OutputStream::Stderr && l.text == "err"));
+    }
+
+    #[test]
+    fn test_streaming_drain_output() {
+        let executor = CommandExecutor::new("sh", &["-c", "echo a; echo b"]);
+        let running = executor.spawn_streaming().expect("Failed to spawn sh");
+
+        let mut received = Vec::new();
+        while !running.is_finished() {
+            received.extend(running.drain_output());
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        received.extend(running.drain_output());
+        running.wait().expect("Failed to wait for sh");
+
+        let texts: Vec<&str> = received.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_default_execution_mode() {
+        assert_eq!(ExecutionMode::default(), ExecutionMode::Streaming);
+    }
+
+    #[test]
+    fn test_execution_mode_from_str() {
+        assert_eq!("pty".parse::<ExecutionMode>().unwrap(), ExecutionMode::Pty);
+        assert_eq!("Streaming".parse::<ExecutionMode>().unwrap(), ExecutionMode::Streaming);
+        assert!("turbo".parse::<ExecutionMode>().is_err());
+    }
+
+    #[test]
+    fn test_signal_death_exit_code() {
+        let executor = CommandExecutor::new("sh", &["-c", "kill -TERM $$"]);
+        let result = executor.run().expect("Failed to run sh");
+
+        assert!(!result.success);
+        assert_eq!(result.signal, Some(15));
+        assert_eq!(result.exit_code, 143);
+    }
+
+    #[test]
+    fn test_stdin_closed() {
+        let executor = CommandExecutor::new("cat", &[]).with_stdin(false);
+        let result = executor.run().expect("Failed to run cat");
+
+        assert!(result.success);
+        assert!(result.stdout.is_empty());
+    }
+
+    #[test]
+    fn test_env_changes() {
+        let executor = CommandExecutor::new("sh", &["-c", "echo \"$CRAB-$HOME\""])
+            .with_clear_env()
+            .with_env("CRAB", "crust")
+            .with_env("HOME", "/nowhere")
+            .without_env("HOME");
+        let result = executor.run().expect("Failed to run sh");
+
+        assert_eq!(result.stdout, "crust-\n");
+    }
+
+    #[test]
+    fn test_timeout_kills_command() {
+        let executor =
+            CommandExecutor::new("sleep", &["5"]).with_timeout(Duration::from_millis(100));
+        let result = executor.run().expect("Failed to run sleep");
+
+        assert!(result.timed_out);
+        assert!(!result.success);
+        assert_eq!(result.exit_code, TIMEOUT_EXIT_CODE);
+        assert!(result.elapsed < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_streaming_timeout_keeps_output() {
+        let executor = CommandExecutor::new("sh", &["-c", "echo started; sleep 5"])
+            .with_timeout(Duration::from_millis(200));
+        let result = executor
+            .spawn_streaming()
+            .and_then(RunningCommand::wait)
+            .expect("Failed to run sh");
+
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code, TIMEOUT_EXIT_CODE);
+        assert_eq!(result.stdout, "started\n");
+        assert!(result.elapsed < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_success_codes() {
+        let executor = CommandExecutor::new("sh", &["-c", "exit 1"]).with_success_codes(&[0, 1]);
+        let result = executor.run().expect("Failed to run sh");
+        assert!(result.success);
+        assert_eq!(result.exit_code, 1);
+
+        let executor = CommandExecutor::new("sh", &["-c", "exit 2"]).with_success_codes(&[0, 1]);
+        assert!(!executor.run().expect("Failed to run sh").success);
+    }
+
+    #[test]
+    fn test_find_program() {
+        let sh = find_program("sh").expect("sh should be on PATH");
+        assert!(sh.is_absolute());
+        assert_eq!(find_program(sh.to_str().unwrap()), Some(sh));
+
+        assert_eq!(find_program("crabcrust-no-such-program"), None);
+        assert_eq!(find_program("./no/such/program"), None);
+        assert_eq!(find_program(""), None);
+    }
+
     #[test]
     fn test_command_string() {
         let executor = CommandExecutor::new("git", &["commit", "-m", "test"]);
@@ -165,6 +846,7 @@ mod tests {
             stderr: "error".to_string(),
             exit_code: 0,
             success: true,
+            ..Default::default()
         };
 
         let combined = result.combined_output();
@@ -0,0 +1,249 @@
+// Execution for output that may be too long to keep in memory
+//
+// Only the first lines of stdout (the head) are read up front, on a
+// background thread. The caller can then wait for the rest of the output
+// (when it turned out to be short) or stream head and rest into a writer
+// such as a pager, without ever holding all of it.
+
+use super::{read_in_background, CommandExecutor, CommandResult, PipeReader};
+use crate::signals::{self, ChildGuard};
+use anyhow::{Context, Result};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdout, Stdio};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+/// Longest head read, however few lines it has
+const MAX_HEAD_BYTES: u64 = 256 * 1024;
+
+/// The first lines of stdout and the pipe to read the rest from
+struct Head {
+    bytes: Vec<u8>,
+    /// stdout ended within the head
+    complete: bool,
+    rest: BufReader<ChildStdout>,
+}
+
+enum HeadState {
+    Reading(JoinHandle<io::Result<Head>>),
+    Read(Head),
+}
+
+/// A command whose stdout is read a screenful at a time
+///
+/// Created by [`CommandExecutor::spawn_paged`]. The timeout set with
+/// [`with_timeout`](CommandExecutor::with_timeout) doesn't apply: how long
+/// paged output takes is up to the reader.
+pub struct PagedCommand {
+    executor: CommandExecutor,
+    child: Child,
+    head: Option<HeadState>,
+    stderr: PipeReader,
+    started: Instant,
+    signals: ChildGuard,
+}
+
+pub(super) fn spawn(executor: &CommandExecutor, max_lines: usize) -> Result<PagedCommand> {
+    let mut cmd = executor.command();
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let started = Instant::now();
+    let mut child = cmd.spawn().with_context(|| {
+        format!("Failed to execute command: {} {:?}", executor.program, executor.args)
+    })?;
+
+    // Pass termination signals on while we wait
+    let signals = signals::forward_to_child(child.id(), None)?;
+
+    let stdout = child.stdout.take().context("Failed to capture stdout")?;
+    let stderr = read_in_background(child.stderr.take());
+    let head = std::thread::spawn(move || read_head(BufReader::new(stdout), max_lines));
+
+    Ok(PagedCommand {
+        executor: executor.clone(),
+        child,
+        head: Some(HeadState::Reading(head)),
+        stderr,
+        started,
+        signals,
+    })
+}
+
+/// Read up to `max_lines` lines (and at most [`MAX_HEAD_BYTES`])
+fn read_head(mut stdout: BufReader<ChildStdout>, max_lines: usize) -> io::Result<Head> {
+    let mut bytes = Vec::new();
+    let mut complete = false;
+
+    for _ in 0..max_lines {
+        let budget = MAX_HEAD_BYTES.saturating_sub(bytes.len() as u64);
+        if budget == 0 {
+            break;
+        }
+        let read = (&mut stdout).take(budget).read_until(b'\n', &mut bytes)?;
+        if read == 0 {
+            complete = true;
+            break;
+        }
+    }
+    // A head that ends exactly at EOF is still complete
+    if !complete && stdout.fill_buf()?.is_empty() {
+        complete = true;
+    }
+
+    Ok(Head {
+        bytes,
+        complete,
+        rest: stdout,
+    })
+}
+
+impl PagedCommand {
+    /// Check whether the head has been read (never blocks)
+    pub fn is_ready(&self) -> bool {
+        match &self.head {
+            Some(HeadState::Reading(thread)) => thread.is_finished(),
+            _ => true,
+        }
+    }
+
+    /// Get the first lines of stdout, waiting for them if needed
+    pub fn head(&mut self) -> Result<&[u8]> {
+        Ok(&self.read_head()?.bytes)
+    }
+
+    /// Check whether all of stdout fit in the head, waiting for it if needed
+    pub fn is_complete(&mut self) -> Result<bool> {
+        Ok(self.read_head()?.complete)
+    }
+
+    fn read_head(&mut self) -> Result<&mut Head> {
+        if let Some(HeadState::Reading(_)) = self.head {
+            let Some(HeadState::Reading(thread)) = self.head.take() else {
+                unreachable!("checked above");
+            };
+            let head = thread
+                .join()
+                .map_err(|_| anyhow::anyhow!("Output reader panicked"))?
+                .context("Failed to read command output")?;
+            self.head = Some(HeadState::Read(head));
+        }
+        match &mut self.head {
+            Some(HeadState::Read(head)) => Ok(head),
+            _ => unreachable!("head is read above"),
+        }
+    }
+
+    /// Wait for the command to exit, keeping everything it printed
+    ///
+    /// Meant for output that turned out to be short
+    /// ([`is_complete`](Self::is_complete)); anything past the head is read
+    /// into memory too.
+    pub fn wait(mut self) -> Result<CommandResult> {
+        let head = self.read_head()?;
+        let mut stdout = std::mem::take(&mut head.bytes);
+        head.rest
+            .read_to_end(&mut stdout)
+            .context("Failed to read command output")?;
+        self.finish(&stdout, false)
+    }
+
+    /// Write all of stdout to `writer` as it arrives, then wait for the command
+    ///
+    /// The result keeps only the head in `stdout` and has `echoed` set. If
+    /// the writer stops accepting output (e.g. the user quit the pager), the
+    /// command is stopped and that counts as success.
+    pub fn pipe_to<W: Write>(mut self, mut writer: W) -> Result<CommandResult> {
+        let head = self.read_head()?;
+        let copied = writer
+            .write_all(&head.bytes)
+            .and_then(|_| io::copy(&mut head.rest, &mut writer))
+            .and_then(|_| writer.flush());
+        let head_bytes = std::mem::take(&mut head.bytes);
+
+        let reader_gone = match copied {
+            Ok(_) => false,
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => true,
+            Err(err) => return Err(err).context("Failed to pass on command output"),
+        };
+        drop(writer);
+        if reader_gone {
+            let _ = self.child.kill();
+        }
+
+        let mut result = self.finish(&head_bytes, true)?;
+        if reader_gone {
+            result.exit_code = 0;
+            result.success = true;
+            result.signal = None;
+        }
+        Ok(result)
+    }
+
+    fn finish(mut self, stdout: &[u8], echoed: bool) -> Result<CommandResult> {
+        // Close our end of stdout so the command can't block writing to it
+        self.head = None;
+        let status = self.child.wait().context("Failed to wait for command")?;
+        self.signals.child_exited(&status);
+        let stderr = self.stderr.collect(false);
+
+        Ok(CommandResult {
+            echoed,
+            ..self.executor.finish(status, stdout, &stderr, self.started, false)
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Writer that hangs up after a few bytes, like a pager the user quit
+    struct QuitEarly(usize);
+
+    impl Write for QuitEarly {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.0 == 0 {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            let n = buf.len().min(self.0);
+            self.0 -= n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_short_output_is_complete() {
+        let mut paged = CommandExecutor::new("echo", &["hi"]).spawn_paged(10).unwrap();
+        assert_eq!(paged.head().unwrap(), b"hi\n");
+        assert!(paged.is_complete().unwrap());
+        assert_eq!(paged.wait().unwrap().stdout, "hi\n");
+    }
+
+    #[test]
+    fn test_long_output_is_piped() {
+        let mut paged = CommandExecutor::new("seq", &["1", "1000"]).spawn_paged(3).unwrap();
+        assert_eq!(paged.head().unwrap(), b"1\n2\n3\n");
+        assert!(!paged.is_complete().unwrap());
+
+        let mut piped = Vec::new();
+        let result = paged.pipe_to(&mut piped).unwrap();
+        assert!(result.success);
+        assert!(result.echoed);
+        assert_eq!(result.stdout, "1\n2\n3\n");
+        assert_eq!(piped.iter().filter(|&&b| b == b'\n').count(), 1000);
+    }
+
+    #[test]
+    fn test_reader_quitting_stops_command() {
+        // `yes` never ends on its own
+        let paged = CommandExecutor::new("yes", &[]).spawn_paged(5).unwrap();
+        let result = paged.pipe_to(QuitEarly(100)).unwrap();
+        assert!(result.success);
+        assert_eq!(result.exit_code, 0);
+    }
+}
@@ -0,0 +1,230 @@
+// Pseudo-terminal execution so wrapped tools see a real terminal
+//
+// The child gets a PTY for stdin/stdout/stderr, so git keeps its colors,
+// pagers, editors and credential prompts. Everything the child writes is
+// mirrored to our stdout as it happens and captured for later inspection.
+
+use super::{CommandExecutor, CommandResult};
+use anyhow::Result;
+
+/// Run the command attached to a pseudo-terminal
+#[cfg(target_os = "linux")]
+pub(crate) fn run(executor: &CommandExecutor) -> Result<CommandResult> {
+    linux::run(executor)
+}
+
+/// Run the command attached to a pseudo-terminal
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn run(_executor: &CommandExecutor) -> Result<CommandResult> {
+    anyhow::bail!("PTY execution is only supported on Linux")
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{CommandExecutor, CommandResult};
+    use crate::signals::{self, copy_window_size};
+    use anyhow::{Context, Result};
+    use std::fs::File;
+    use std::io::{self, Read, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::Instant;
+
+    /// How long to wait for I/O before re-checking whether the child exited
+    const POLL_INTERVAL_MS: i32 = 50;
+
+    pub(super) fn run(executor: &CommandExecutor) -> Result<CommandResult> {
+        let (master, slave) = open_pty()?;
+        copy_window_size(io::stdout().as_raw_fd(), master.as_raw_fd());
+
+        let mut cmd = executor.command();
+        cmd.stdin(Stdio::from(slave.try_clone()?));
+        cmd.stdout(Stdio::from(slave.try_clone()?));
+        cmd.stderr(Stdio::from(slave));
+
+        // SAFETY: only async-signal-safe calls between fork and exec
+        unsafe {
+            cmd.pre_exec(|| {
+                // New session so the PTY becomes the controlling terminal
+                if libc::setsid() == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                if libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let started = Instant::now();
+        let mut child = cmd.spawn().with_context(|| {
+            format!("Failed to execute command: {} {:?}", executor.program, executor.args)
+        })?;
+
+        // Close our copies of the slave so reads hit EOF once the child is done
+        drop(cmd);
+
+        // Keyboard signals arrive as input bytes; forward kill()s and resizes
+        let signals = signals::forward_to_child(child.id(), Some(master.as_raw_fd()))?;
+
+        let raw_mode = RawModeGuard::enable();
+        let stop = Arc::new(AtomicBool::new(false));
+        let input = forward_input(master.try_clone()?, stop.clone());
+
+        let mut captured = Vec::new();
+        let mut reader = File::from(master);
+        let mut stdout = io::stdout();
+        let mut buf = [0u8; 4096];
+
+        let (status, timed_out) = loop {
+            if executor.timed_out(started) {
+                let _ = child.kill();
+                break (child.wait()?, true);
+            }
+
+            if poll_readable(reader.as_raw_fd(), POLL_INTERVAL_MS) {
+                match reader.read(&mut buf) {
+                    Ok(0) => break (child.wait()?, false),
+                    Ok(n) => {
+                        stdout.write_all(&buf[..n])?;
+                        stdout.flush()?;
+                        captured.extend_from_slice(&buf[..n]);
+                        continue;
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    // Linux reports EIO once every slave descriptor is closed
+                    Err(e) if e.raw_os_error() == Some(libc::EIO) => break (child.wait()?, false),
+                    Err(e) => return Err(e).context("Failed to read from pseudo-terminal"),
+                }
+            }
+
+            // A background grandchild may keep the slave open after the child exits
+            if let Some(status) = child.try_wait()? {
+                break (status, false);
+            }
+        };
+
+        signals.child_exited(&status);
+
+        stop.store(true, Ordering::Relaxed);
+        let _ = input.join();
+        drop(raw_mode);
+
+        Ok(CommandResult {
+            echoed: true,
+            ..executor.finish(status, &captured, &[], started, timed_out)
+        })
+    }
+
+    /// Open a master/slave PTY pair with close-on-exec set on the master
+    fn open_pty() -> Result<(OwnedFd, OwnedFd)> {
+        let mut master: RawFd = -1;
+        let mut slave: RawFd = -1;
+
+        // SAFETY: openpty only writes the two descriptors on success
+        let rc = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error()).context("Failed to open pseudo-terminal");
+        }
+
+        // SAFETY: both descriptors were just returned by openpty and are owned here
+        let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+        unsafe {
+            libc::fcntl(master.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+
+        Ok((master, slave))
+    }
+
+    /// Wait up to `timeout_ms` for a descriptor to become readable
+    fn poll_readable(fd: RawFd, timeout_ms: i32) -> bool {
+        let mut pfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pfd is a valid pollfd for the duration of the call
+        let rc = unsafe { libc::poll(&mut pfd, 1, timeout_ms) };
+        rc > 0 && pfd.revents != 0
+    }
+
+    /// Copy our stdin into the PTY until `stop` is set or stdin closes
+    fn forward_input(master: OwnedFd, stop: Arc<AtomicBool>) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            let mut writer = File::from(master);
+            let stdin = io::stdin().as_raw_fd();
+            let mut buf = [0u8; 1024];
+
+            while !stop.load(Ordering::Relaxed) {
+                if !poll_readable(stdin, POLL_INTERVAL_MS) {
+                    continue;
+                }
+
+                // Read the raw descriptor so no input is left in std's buffer
+                // SAFETY: buf is valid for buf.len() bytes
+                let n = unsafe { libc::read(stdin, buf.as_mut_ptr().cast(), buf.len()) };
+                if n <= 0 || writer.write_all(&buf[..n as usize]).is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Puts our terminal in raw mode so keystrokes reach the child unmodified
+    struct RawModeGuard {
+        enabled: bool,
+    }
+
+    impl RawModeGuard {
+        fn enable() -> Self {
+            // SAFETY: isatty has no preconditions
+            let is_tty = unsafe { libc::isatty(libc::STDIN_FILENO) } == 1;
+            let enabled = is_tty && crossterm::terminal::enable_raw_mode().is_ok();
+            Self { enabled }
+        }
+    }
+
+    impl Drop for RawModeGuard {
+        fn drop(&mut self) {
+            if self.enabled {
+                let _ = crossterm::terminal::disable_raw_mode();
+            }
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_sees_a_terminal() {
+        let executor = CommandExecutor::new("sh", &["-c", "test -t 1 && echo tty"]);
+        let result = run(&executor).expect("Failed to run sh in a PTY");
+
+        assert!(result.success);
+        assert!(result.echoed);
+        assert!(result.stdout.contains("tty"));
+    }
+
+    #[test]
+    fn test_exit_code_propagates() {
+        let executor = CommandExecutor::new("sh", &["-c", "exit 3"]);
+        let result = run(&executor).expect("Failed to run sh in a PTY");
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 3);
+    }
+}
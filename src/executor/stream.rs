@@ -0,0 +1,178 @@
+// Line-by-line output streaming for wrapped commands
+
+use std::io;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Which output stream a line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// A single line of output captured from a running command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    /// Stream the line was written to
+    pub stream: OutputStream,
+    /// Line contents without the terminator
+    pub text: String,
+    /// How the line ended: `'\n'`, `'\r'` (progress update), or `None` at end of output
+    pub terminator: Option<char>,
+}
+
+impl OutputLine {
+    /// Check if this line is a progress update that the next line overwrites
+    pub fn is_progress(&self) -> bool {
+        self.terminator == Some('\r')
+    }
+}
+
+/// Splits a byte stream into lines on `\n`, `\r\n` and bare `\r`
+///
+/// Bare carriage returns are how tools like git redraw progress counters,
+/// so they are reported as their own line terminator.
+#[derive(Debug, Default)]
+pub struct LineSplitter {
+    buffer: Vec<u8>,
+    pending_cr: bool,
+}
+
+impl LineSplitter {
+    /// Create an empty splitter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk of bytes, returning every line it completes
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<(String, Option<char>)> {
+        let mut lines = Vec::new();
+
+        for &byte in bytes {
+            if self.pending_cr {
+                self.pending_cr = false;
+                if byte == b'\n' {
+                    lines.push(self.take_line(Some('\n')));
+                    continue;
+                }
+                lines.push(self.take_line(Some('\r')));
+            }
+
+            match byte {
+                b'\n' => lines.push(self.take_line(Some('\n'))),
+                b'\r' => self.pending_cr = true,
+                _ => self.buffer.push(byte),
+            }
+        }
+
+        lines
+    }
+
+    /// Flush whatever is left once the stream has closed
+    pub fn finish(&mut self) -> Option<(String, Option<char>)> {
+        if self.pending_cr {
+            self.pending_cr = false;
+            Some(self.take_line(Some('\r')))
+        } else if !self.buffer.is_empty() {
+            Some(self.take_line(None))
+        } else {
+            None
+        }
+    }
+
+    fn take_line(&mut self, terminator: Option<char>) -> (String, Option<char>) {
+        let text = String::from_utf8_lossy(&self.buffer).to_string();
+        self.buffer.clear();
+        (text, terminator)
+    }
+}
+
+/// Read a child stream to the end, forwarding each line as it arrives
+///
+/// Lines are appended to the shared `transcript` and sent to `tx` under the
+/// same lock, so both observe stdout and stderr in one consistent order.
+/// Returns the raw bytes read for the buffered `CommandResult` fields.
+pub(crate) async fn forward<R: AsyncRead + Unpin>(
+    mut reader: R,
+    stream: OutputStream,
+    tx: Sender<OutputLine>,
+    transcript: Arc<Mutex<Vec<OutputLine>>>,
+) -> io::Result<Vec<u8>> {
+    let mut raw = Vec::new();
+    let mut splitter = LineSplitter::new();
+    let mut chunk = [0u8; 4096];
+
+    let emit = |(text, terminator): (String, Option<char>)| {
+        let line = OutputLine {
+            stream,
+            text,
+            terminator,
+        };
+        let mut transcript = transcript.lock().unwrap_or_else(|e| e.into_inner());
+        // The receiver may already be gone; the transcript still keeps the line
+        let _ = tx.send(line.clone());
+        transcript.push(line);
+    };
+
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        raw.extend_from_slice(&chunk[..n]);
+        splitter.push(&chunk[..n]).into_iter().for_each(emit);
+    }
+
+    if let Some(line) = splitter.finish() {
+        emit(line);
+    }
+
+    Ok(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_newlines() {
+        let mut splitter = LineSplitter::new();
+        let lines = splitter.push(b"one\ntwo\r\nthr");
+        assert_eq!(
+            lines,
+            vec![
+                ("one".to_string(), Some('\n')),
+                ("two".to_string(), Some('\n')),
+            ]
+        );
+
+        assert!(splitter.push(b"ee").is_empty());
+        assert_eq!(splitter.finish(), Some(("three".to_string(), None)));
+        assert_eq!(splitter.finish(), None);
+    }
+
+    #[test]
+    fn test_split_progress_updates() {
+        let mut splitter = LineSplitter::new();
+        let mut lines = splitter.push(b"10%\r50%\r");
+        lines.extend(splitter.push(b"100%\n"));
+
+        assert_eq!(
+            lines,
+            vec![
+                ("10%".to_string(), Some('\r')),
+                ("50%".to_string(), Some('\r')),
+                ("100%".to_string(), Some('\n')),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_crlf_across_chunks() {
+        let mut splitter = LineSplitter::new();
+        assert!(splitter.push(b"done\r").is_empty());
+        assert_eq!(splitter.push(b"\n"), vec![("done".to_string(), Some('\n'))]);
+    }
+}
@@ -5,6 +5,9 @@ pub mod rendering;
 pub mod animation;
 pub mod executor;
 pub mod wrapper;
+pub mod signals;
+pub mod config;
+pub mod shell;
 
 // Video conversion (optional feature)
 #[cfg(any(feature = "gif", feature = "video"))]
@@ -15,15 +18,21 @@ pub mod video;
 pub mod dmd_library;
 
 // Re-export commonly used types
-pub use braille::{BrailleGrid, Color};
-pub use rendering::{RenderMode, TerminalRenderer};
+pub use braille::{BrailleGrid, CellEncoding, Color, ColorPolicy, Font, Glyph, TextAlign, TextStyle};
+pub use config::Config;
+pub use rendering::{
+    ColorDepth, Frame, FrameBuffer, FrameSink, OutputPane, RenderMode, RenderStats, RenderTarget,
+    TerminalRenderer, TerminalSupport, WriterSink,
+};
 pub use animation::{
     Animation, AnimationPlayer,
     SpinnerAnimation, RocketAnimation, SaveAnimation, DownloadAnimation, MergeAnimation,
     RabbitAnimation, FireworksAnimation, BabyAnnouncementAnimation, ConfettiAnimation, TrophyAnimation,
-    FrameBasedAnimation, MatrixRainAnimation
+    FrameBasedAnimation, MatrixRainAnimation, ProgressAnimation, AnimationRegistry
+};
+pub use executor::{
+    CommandExecutor, CommandResult, ExecutionMode, OutputLine, OutputStream, RunningCommand,
 };
-pub use executor::{CommandExecutor, CommandResult};
 
 // Video conversion utilities (optional feature)
 #[cfg(any(feature = "gif", feature = "video"))]
@@ -2,12 +2,15 @@
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use crabcrust::config::{AnimationSpec, Config, Outcome};
+use crabcrust::executor::{self, CommandExecutor};
+use crabcrust::shell::Shell;
+use crabcrust::signals::Interrupted;
+use crabcrust::wrapper::cargo::CargoWrapper;
 use crabcrust::wrapper::git::GitWrapper;
-use crabcrust::{
-    AnimationPlayer, BabyAnnouncementAnimation, ConfettiAnimation, DownloadAnimation,
-    FireworksAnimation, MergeAnimation, RabbitAnimation, RocketAnimation, SaveAnimation,
-    SpinnerAnimation, TrophyAnimation, MatrixRainAnimation,
-};
+use crabcrust::wrapper::CliWrapper;
+use crabcrust::animation::{AnimationParams, AnimationRegistry};
+use crabcrust::{AnimationPlayer, CellEncoding, ExecutionMode};
 
 #[cfg(any(feature = "gif", feature = "video"))]
 use crabcrust::FrameBasedAnimation;
@@ -21,6 +24,10 @@ use std::path::PathBuf;
 #[command(about = "Add arcade-style animations to your CLI tools 🦀✨", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// How wrapped commands run: blocking, concurrent, streaming or pty
+    #[arg(long, value_name = "MODE")]
+    exec_mode: Option<ExecutionMode>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -34,15 +41,57 @@ enum Commands {
         args: Vec<String>,
     },
 
+    /// Cargo wrapper with build progress and test-aware animations
+    Cargo {
+        /// Cargo arguments (e.g., test --release)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Run any command with running/success/failure animations
+    Run {
+        /// Animation while the command runs
+        #[arg(long, value_name = "NAME")]
+        running: Option<String>,
+
+        /// Animation when the command succeeds
+        #[arg(long, value_name = "NAME")]
+        success: Option<String>,
+
+        /// Animation when the command fails
+        #[arg(long, value_name = "NAME")]
+        failure: Option<String>,
+
+        /// Command and its arguments (e.g., -- make deploy)
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Print shell functions that run git and cargo through crabcrust
+    ///
+    /// Add `eval "$(crabcrust init bash)"` to ~/.bashrc (likewise for zsh), or
+    /// `crabcrust init fish | source` to config.fish.
+    Init {
+        /// Shell to set up: bash, zsh or fish
+        shell: Shell,
+    },
+
     /// Test animations
     Demo {
-        /// Which animation to demo: spinner, rocket, save, download, merge, rabbit, fireworks, baby, confetti, trophy, matrix, all
-        #[arg(default_value = "all")]
+        #[arg(default_value = "all", help = demo_animation_help())]
         animation: String,
 
         /// Use fullscreen mode instead of inline (clears terminal)
         #[arg(short, long)]
         fullscreen: bool,
+
+        /// Print how many bytes were written per frame afterwards
+        #[arg(long)]
+        stats: bool,
+
+        /// Draw cells with: braille, halfblock, quadrant, sextant or ascii
+        #[arg(long)]
+        cells: Option<CellEncoding>,
     },
 
     /// Convert video/GIF to Braille animation (requires 'gif' or 'video' feature)
@@ -80,9 +129,23 @@ enum Commands {
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    match run(cli) {
+        Err(err) => match err.downcast_ref::<Interrupted>() {
+            // The terminal is already restored; exit like a shell would
+            Some(interrupted) => std::process::exit(interrupted.exit_code()),
+            None => Err(err),
+        },
+        ok => ok,
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Git { args } => {
-            let mut wrapper = GitWrapper::new()?;
+            let mut wrapper = GitWrapper::new()?.with_config(load_config_or_default());
+            if let Some(mode) = cli.exec_mode {
+                wrapper = wrapper.with_mode(mode);
+            }
             let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
             let result = wrapper.run(&args_refs)?;
 
@@ -90,7 +153,73 @@ fn main() -> Result<()> {
             std::process::exit(result.exit_code);
         }
 
-        Commands::Demo { animation, fullscreen } => {
+        Commands::Cargo { args } => {
+            let mut wrapper = CargoWrapper::new()?.with_config(load_config_or_default());
+            if let Some(mode) = cli.exec_mode {
+                wrapper = wrapper.with_mode(mode);
+            }
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            let result = wrapper.run(&args_refs)?;
+
+            // Exit with the same code as cargo
+            std::process::exit(result.exit_code);
+        }
+
+        Commands::Run {
+            running,
+            success,
+            failure,
+            command,
+        } => {
+            let (name, args) = command.split_first().expect("clap requires a command");
+            let Some(program) = executor::find_program(name) else {
+                eprintln!("crabcrust: command not found: {}", name);
+                std::process::exit(executor::NOT_FOUND_EXIT_CODE);
+            };
+
+            // Animations given on the command line beat the config files
+            let mut config = load_config_or_default();
+            let key = program.file_name().and_then(|n| n.to_str()).unwrap_or(name);
+            let subcommand = args.first().map(String::as_str);
+            let names = AnimationRegistry::global_names();
+            for (outcome, animation) in [
+                (Outcome::Running, running),
+                (Outcome::Success, success),
+                (Outcome::Failure, failure),
+            ] {
+                if let Some(animation) = animation {
+                    if !names.contains(&animation) {
+                        anyhow::bail!(
+                            "Unknown animation '{}' (expected one of: {})",
+                            animation,
+                            names.join(", ")
+                        );
+                    }
+                    config.set_animation(key, subcommand, outcome, AnimationSpec::named(&animation));
+                }
+            }
+
+            let mut wrapper = CliWrapper::new()?.with_config(config);
+            if let Some(mode) = cli.exec_mode {
+                wrapper = wrapper.with_mode(mode);
+            }
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            let executor = CommandExecutor::new(&program.to_string_lossy(), &args_refs);
+            let result = wrapper.run_with_default_animations(executor)?;
+
+            // Exit with the same code as the command
+            std::process::exit(result.exit_code);
+        }
+
+        Commands::Init { shell } => {
+            let program = std::env::current_exe()
+                .ok()
+                .and_then(|path| path.to_str().map(String::from))
+                .unwrap_or_else(|| "crabcrust".to_string());
+            print!("{}", shell.script(&program));
+        }
+
+        Commands::Demo { animation, fullscreen, stats, cells } => {
             // Use inline mode by default for non-disruptive demos
             // Only use fullscreen if explicitly requested
             let mut player = if fullscreen {
@@ -99,102 +228,44 @@ fn main() -> Result<()> {
                 println!("💡 Tip: Add --fullscreen flag for immersive fullscreen mode\n");
                 AnimationPlayer::inline_auto()? // Inline mode with 1/3 terminal height
             };
+            player = Config::load()?.apply_to(player);
+            if let Some(encoding) = cells {
+                player = player.with_cell_encoding(encoding);
+            }
 
-            match animation.as_str() {
-                "spinner" => {
-                    println!("🌀 Spinner Animation Demo");
-                    player.play_for(SpinnerAnimation::new(), Duration::from_secs(3))?;
-                }
-                "rocket" => {
-                    println!("🚀 Rocket Animation Demo");
-                    player.play(RocketAnimation::new(Duration::from_secs(2)))?;
-                }
-                "save" => {
-                    println!("💾 Save Animation Demo");
-                    player.play(SaveAnimation::default())?;
-                }
-                "download" => {
-                    println!("⬇️  Download Animation Demo");
-                    player.play(DownloadAnimation::default())?;
-                }
-                "merge" => {
-                    println!("🔀 Merge Animation Demo");
-                    player.play(MergeAnimation::default())?;
-                }
-                "rabbit" => {
-                    println!("🐰 White Rabbit Animation Demo - I'm late! I'm late!");
-                    player.play(RabbitAnimation::default())?;
+            let animations = AnimationRegistry::global_animations();
+            if animation == "all" {
+                println!("🎮 Running all animations...\n");
+
+                // Frame-based animations need a GIF to play
+                let demos = animations.iter().filter(|info| !info.has_tag("frames"));
+                for (i, info) in demos.enumerate() {
+                    if i > 0 {
+                        std::thread::sleep(Duration::from_millis(500));
+                        println!();
+                    }
+                    println!("{}. {}", i + 1, info.description);
+                    play_demo(&mut player, &info.name)?;
                 }
-                "fireworks" => {
-                    println!("🎆 Fireworks Animation Demo");
-                    player.play(FireworksAnimation::default())?;
-                }
-                "baby" => {
-                    println!("👶 Baby Announcement Demo - Congratulations, you're the father!");
-                    player.play(BabyAnnouncementAnimation::default())?;
-                }
-                "confetti" => {
-                    println!("🎊 Confetti Animation Demo");
-                    player.play(ConfettiAnimation::default())?;
-                }
-                "trophy" => {
-                    println!("🏆 Trophy Animation Demo - You're a champion!");
-                    player.play(TrophyAnimation::default())?;
-                }
-                "matrix" => {
-                    println!("🟢 Matrix Rain Demo - Decoding Reality...");
-                    let demo_text = "The Matrix has you...\nFollow the white rabbit.\nKnock, knock, Neo.";
-                    player.play(MatrixRainAnimation::new(demo_text.to_string()))?;
-                }
-                "all" | _ => {
-                    println!("🎮 Running all animations...\n");
-
-                    println!("1. Spinner Animation");
-                    player.play_for(SpinnerAnimation::new(), Duration::from_secs(2))?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n2. Save Animation");
-                    player.play(SaveAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n3. Rocket Animation");
-                    player.play(RocketAnimation::new(Duration::from_secs(2)))?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n4. Download Animation");
-                    player.play(DownloadAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
 
-                    println!("\n5. Merge Animation");
-                    player.play(MergeAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n6. White Rabbit - I'm late!");
-                    player.play(RabbitAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n7. Fireworks Celebration!");
-                    player.play(FireworksAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n8. Baby Announcement - It's a Commit!");
-                    player.play(BabyAnnouncementAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n9. Confetti Party!");
-                    player.play(ConfettiAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n10. Trophy - You're a Winner!");
-                    player.play(TrophyAnimation::default())?;
-                    std::thread::sleep(Duration::from_millis(500));
-
-                    println!("\n11. Matrix Rain - Decoding Reality...");
-                    let demo_text = "Wake up, Neo...\nThe Matrix has you.\nFollow the white rabbit.";
-                    player.play(MatrixRainAnimation::new(demo_text.to_string()))?;
-
-                    println!("\n✨ Demo complete! What a show!");
+                println!("\n✨ Demo complete! What a show!");
+            } else {
+                if let Some(info) = animations.iter().find(|info| info.name == animation) {
+                    println!("🎮 {}", info.description);
                 }
+                play_demo(&mut player, &animation)?;
+            }
+
+            // Report once the player has restored the terminal
+            let render_stats = player.render_stats();
+            drop(player);
+            if let Some(render_stats) = render_stats.filter(|_| stats) {
+                println!(
+                    "📊 {} frames, {} bytes ({:.0} bytes/frame)",
+                    render_stats.frames,
+                    render_stats.bytes,
+                    render_stats.bytes_per_frame()
+                );
             }
         }
 
@@ -249,3 +320,41 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Load the configuration for a wrapped command
+///
+/// A broken config file mustn't stop the command itself, so the error is
+/// reported and the defaults are used.
+fn load_config_or_default() -> Config {
+    Config::load().unwrap_or_else(|err| {
+        eprintln!("crabcrust: {} (using the default settings)", err);
+        Config::default()
+    })
+}
+
+/// Help for the demo's animation argument, listing the registered animations
+fn demo_animation_help() -> String {
+    // Frame-based animations need a GIF to play
+    let names: Vec<String> = AnimationRegistry::global_animations()
+        .into_iter()
+        .filter(|info| !info.has_tag("frames"))
+        .map(|info| info.name)
+        .collect();
+    format!("Which animation to demo: {}, all", names.join(", "))
+}
+
+/// Play a registered animation for the demo
+fn play_demo(player: &mut AnimationPlayer, name: &str) -> Result<()> {
+    let params = AnimationParams {
+        text: Some("Wake up, Neo...\nThe Matrix has you.\nFollow the white rabbit.".to_string()),
+        ..Default::default()
+    };
+    let animation = AnimationRegistry::create_global(name, &params)?;
+
+    // Open-ended animations like the spinner get a fixed slot
+    if animation.duration().is_some() {
+        player.play(animation)
+    } else {
+        player.play_for(animation, Duration::from_secs(3))
+    }
+}
@@ -0,0 +1,200 @@
+// How many colors the terminal can show
+//
+// Animations use 24-bit colors. Terminals that can't show them get the
+// nearest color of the xterm 256-color palette or the 16 ANSI colors, and
+// monochrome terminals (or NO_COLOR) get none at all.
+
+use crate::braille::Color;
+use anyhow::bail;
+use crossterm::style;
+use std::fmt;
+use std::str::FromStr;
+
+/// Colors a terminal supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    /// 24-bit RGB
+    #[default]
+    TrueColor,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// The 16 ANSI colors
+    Ansi16,
+    /// No colors
+    Monochrome,
+}
+
+/// Intensities of the 6×6×6 color cube in the xterm 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 ANSI colors as xterm draws them by default
+const ANSI_PALETTE: [(style::Color, Color); 16] = [
+    (style::Color::Black, Color::new(0, 0, 0)),
+    (style::Color::DarkRed, Color::new(205, 0, 0)),
+    (style::Color::DarkGreen, Color::new(0, 205, 0)),
+    (style::Color::DarkYellow, Color::new(205, 205, 0)),
+    (style::Color::DarkBlue, Color::new(0, 0, 238)),
+    (style::Color::DarkMagenta, Color::new(205, 0, 205)),
+    (style::Color::DarkCyan, Color::new(0, 205, 205)),
+    (style::Color::Grey, Color::new(229, 229, 229)),
+    (style::Color::DarkGrey, Color::new(127, 127, 127)),
+    (style::Color::Red, Color::new(255, 0, 0)),
+    (style::Color::Green, Color::new(0, 255, 0)),
+    (style::Color::Yellow, Color::new(255, 255, 0)),
+    (style::Color::Blue, Color::new(92, 92, 255)),
+    (style::Color::Magenta, Color::new(255, 0, 255)),
+    (style::Color::Cyan, Color::new(0, 255, 255)),
+    (style::Color::White, Color::new(255, 255, 255)),
+];
+
+impl ColorDepth {
+    /// Detect the color depth from a variable lookup
+    ///
+    /// `COLORTERM=truecolor` (or `24bit`) and terminals known to support
+    /// 24-bit color get it; otherwise `TERM` decides between 256 and 16
+    /// colors. `NO_COLOR` is left to the caller.
+    pub fn from_env<F>(var: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let colorterm = var("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+        let term = var("TERM").unwrap_or_default().to_ascii_lowercase();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            Self::TrueColor
+        } else if term == "dumb" {
+            Self::Monochrome
+        } else if var("WT_SESSION").is_some()
+            || matches!(program.as_str(), "iTerm.app" | "WezTerm" | "vscode" | "ghostty")
+        {
+            Self::TrueColor
+        } else if term.contains("256color") || program == "Apple_Terminal" {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// Convert a color to the nearest one the terminal can show
+    ///
+    /// `None` in monochrome.
+    pub fn quantize(self, color: Color) -> Option<style::Color> {
+        match self {
+            Self::TrueColor => Some(style::Color::Rgb {
+                r: color.r,
+                g: color.g,
+                b: color.b,
+            }),
+            Self::Ansi256 => Some(style::Color::AnsiValue(nearest_256(color))),
+            Self::Ansi16 => ANSI_PALETTE
+                .iter()
+                .min_by_key(|(_, candidate)| distance(color, *candidate))
+                .map(|(ansi, _)| *ansi),
+            Self::Monochrome => None,
+        }
+    }
+}
+
+impl fmt::Display for ColorDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+            Self::Monochrome => "none",
+        })
+    }
+}
+
+impl FromStr for ColorDepth {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(Self::TrueColor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Ansi16),
+            "none" | "monochrome" => Ok(Self::Monochrome),
+            _ => bail!("Unknown color depth '{}' (expected truecolor, 256, 16 or none)", s),
+        }
+    }
+}
+
+/// Find the closest color in the xterm 256-color palette
+///
+/// Only the color cube and the gray ramp are considered; the first 16
+/// entries vary between terminals.
+fn nearest_256(color: Color) -> u8 {
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(value))
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(color.r), level(color.g), level(color.b));
+    let cube = Color::new(CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+    let cube_index = 16 + 36 * r as u8 + 6 * g as u8 + b as u8;
+
+    // Grays run from 8 to 238 in steps of 10
+    let average = (color.r as u16 + color.g as u16 + color.b as u16) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * step;
+    let gray = Color::new(gray_level, gray_level, gray_level);
+
+    if distance(color, gray) < distance(color, cube) {
+        232 + step
+    } else {
+        cube_index
+    }
+}
+
+/// Perceptual distance between two colors (squared "redmean" distance)
+///
+/// Weighs the channels the way the eye does, depending on how red the colors
+/// are; cheap enough to run for every cell of every frame.
+fn distance(a: Color, b: Color) -> u32 {
+    let mean_r = (a.r as i32 + b.r as i32) / 2;
+    let dr = a.r as i32 - b.r as i32;
+    let dg = a.g as i32 - b.g as i32;
+    let db = a.b as i32 - b.b as i32;
+    ((((512 + mean_r) * dr * dr) >> 8) + 4 * dg * dg + (((767 - mean_r) * db * db) >> 8)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> ColorDepth {
+        ColorDepth::from_env(|key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_detect_color_depth() {
+        assert_eq!(detect(&[("COLORTERM", "truecolor"), ("TERM", "xterm-256color")]), ColorDepth::TrueColor);
+        assert_eq!(detect(&[("TERM", "tmux-256color")]), ColorDepth::Ansi256);
+        assert_eq!(detect(&[("TERM", "xterm"), ("TERM_PROGRAM", "Apple_Terminal")]), ColorDepth::Ansi256);
+        assert_eq!(detect(&[("TERM", "xterm")]), ColorDepth::Ansi16);
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorDepth::Monochrome);
+        assert_eq!("256".parse::<ColorDepth>().unwrap(), ColorDepth::Ansi256);
+        assert!("8".parse::<ColorDepth>().is_err());
+    }
+
+    #[test]
+    fn test_quantize() {
+        let orange = Color::new(255, 128, 0);
+        assert_eq!(
+            ColorDepth::TrueColor.quantize(orange),
+            Some(style::Color::Rgb { r: 255, g: 128, b: 0 })
+        );
+        // 255,135,0 in the cube
+        assert_eq!(ColorDepth::Ansi256.quantize(orange), Some(style::Color::AnsiValue(208)));
+        assert_eq!(ColorDepth::Ansi256.quantize(Color::new(128, 128, 128)), Some(style::Color::AnsiValue(244)));
+        assert_eq!(ColorDepth::Ansi16.quantize(Color::new(250, 20, 30)), Some(style::Color::Red));
+        assert_eq!(ColorDepth::Ansi16.quantize(Color::new(30, 30, 30)), Some(style::Color::Black));
+        assert_eq!(ColorDepth::Monochrome.quantize(orange), None);
+    }
+}
@@ -0,0 +1,431 @@
+// Incremental drawing of inline frames
+//
+// Redrawing every cell at 60 fps with a color change around each one floods
+// slow links. `DiffRenderer` keeps the previous frame and writes only the
+// cells that changed: changed cells next to each other are printed without
+// cursor moves in between, and colors are only set when they change. Each
+// frame is assembled in memory and written at once.
+
+use super::{shown_cell, ColorDepth, OutputPane};
+use crate::braille::{BrailleGrid, CellEncoding};
+use anyhow::Result;
+use crossterm::{
+    cursor, queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+};
+use std::io::Write;
+
+/// Bytes written for the frames drawn so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderStats {
+    /// Frames drawn
+    pub frames: u64,
+    /// Bytes written in total
+    pub bytes: u64,
+    /// Bytes written for the most recent frame
+    pub last_frame_bytes: usize,
+}
+
+impl RenderStats {
+    /// Count a frame of `bytes` bytes
+    pub(super) fn record(&mut self, bytes: usize) {
+        self.frames += 1;
+        self.bytes += bytes as u64;
+        self.last_frame_bytes = bytes;
+    }
+
+    /// Get the average number of bytes per frame
+    pub fn bytes_per_frame(&self) -> f64 {
+        if self.frames == 0 {
+            0.0
+        } else {
+            self.bytes as f64 / self.frames as f64
+        }
+    }
+}
+
+/// One row of the area as drawn
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    /// Grid cells: a character and its foreground and background colors (as
+    /// shown) each
+    Cells(Vec<(char, Option<Color>, Option<Color>)>),
+    /// A pane line, redrawn whole when it changes (its characters may be wide)
+    Text(String, Option<Color>),
+}
+
+/// Draws frames into an inline area, writing only what changed
+///
+/// Between frames the cursor rests at the start of the line below the area.
+pub(super) struct DiffRenderer {
+    /// Rows drawn by the previous frame
+    previous: Option<Vec<Row>>,
+    /// Row the cursor is on, counted from the top of the area
+    cursor_row: u16,
+    color_depth: ColorDepth,
+    encoding: CellEncoding,
+}
+
+impl DiffRenderer {
+    /// Create a renderer whose cursor starts `cursor_row` rows below the top
+    /// of the area, at column 0
+    pub(super) fn new(cursor_row: u16, color_depth: ColorDepth) -> Self {
+        Self {
+            previous: None,
+            cursor_row,
+            color_depth,
+            encoding: CellEncoding::default(),
+        }
+    }
+
+    /// Draw colors at another depth, starting with a full frame
+    pub(super) fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        self.color_depth = color_depth;
+        self.invalidate();
+    }
+
+    /// Draw cells with other characters, starting with a full frame
+    pub(super) fn set_cell_encoding(&mut self, encoding: CellEncoding) {
+        self.encoding = encoding;
+        self.invalidate();
+    }
+
+    /// Draw a frame, with the pane's rows (if any) beneath the grid, and
+    /// get the number of bytes written
+    pub(super) fn render<W: Write>(
+        &mut self,
+        out: &mut W,
+        grid: &BrailleGrid,
+        pane: Option<&OutputPane>,
+        width: u16,
+    ) -> Result<usize> {
+        let rows = self.rows(grid, pane, width);
+        let mut buf = Vec::new();
+
+        match self.previous.take() {
+            Some(previous) if Self::same_shape(&previous, &rows) => {
+                self.queue_changes(&mut buf, &previous, &rows)?;
+            }
+            _ => self.queue_all(&mut buf, &rows)?,
+        }
+        self.cursor_row = rows.len() as u16;
+        self.previous = Some(rows);
+
+        out.write_all(&buf)?;
+        out.flush()?;
+        Ok(buf.len())
+    }
+
+    /// Forget the previous frame, so the next one is drawn in full
+    pub(super) fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    fn rows(&self, grid: &BrailleGrid, pane: Option<&OutputPane>, width: u16) -> Vec<Row> {
+        let mut rows: Vec<Row> = (0..grid.height())
+            .map(|y| {
+                Row::Cells(
+                    (0..grid.width())
+                        .map(|x| shown_cell(grid, x, y, self.encoding, self.color_depth))
+                        .collect(),
+                )
+            })
+            .collect();
+
+        if let Some(pane) = pane {
+            let mut lines = pane.lines();
+            for _ in 0..pane.rows() {
+                // Unused pane rows are drawn blank
+                let (text, color) = lines.next().unwrap_or(("", None));
+                let visible = text
+                    .chars()
+                    .map(|c| if c == '\t' { ' ' } else { c })
+                    .filter(|c| !c.is_control())
+                    .take(width as usize)
+                    .collect();
+                rows.push(Row::Text(visible, color.and_then(|c| self.color_depth.quantize(c))));
+            }
+        }
+        rows
+    }
+
+    fn same_shape(previous: &[Row], rows: &[Row]) -> bool {
+        previous.len() == rows.len()
+            && previous.iter().zip(rows).all(|pair| match pair {
+                (Row::Cells(a), Row::Cells(b)) => a.len() == b.len(),
+                (Row::Text(..), Row::Text(..)) => true,
+                _ => false,
+            })
+    }
+
+    /// Draw every row from the top of the area, ending below it
+    fn queue_all(&self, buf: &mut Vec<u8>, rows: &[Row]) -> Result<()> {
+        if self.cursor_row > 0 {
+            queue!(buf, cursor::MoveUp(self.cursor_row))?;
+        }
+        queue!(buf, Print('\r'))?;
+
+        let mut pen = Pen::default();
+        for row in rows {
+            match row {
+                Row::Cells(cells) => {
+                    for &(ch, fg, bg) in cells {
+                        pen.print(buf, ch, fg, bg)?;
+                    }
+                }
+                Row::Text(text, color) => pen.print_line(buf, text, *color)?,
+            }
+            // A new line scrolled in would be filled with the background
+            pen.clear_background(buf)?;
+            queue!(buf, Print("\r\n"))?;
+        }
+        pen.reset(buf)?;
+        Ok(())
+    }
+
+    /// Draw the cells and lines that differ from `previous`, ending below
+    /// the area
+    fn queue_changes(&self, buf: &mut Vec<u8>, previous: &[Row], rows: &[Row]) -> Result<()> {
+        let mut pen = Pen::default();
+        let mut cursor = Cursor {
+            row: self.cursor_row,
+            column: Some(0),
+        };
+
+        for (y, (old, new)) in previous.iter().zip(rows).enumerate() {
+            let y = y as u16;
+            match (old, new) {
+                (Row::Cells(old), Row::Cells(new)) => {
+                    for (x, (old, &(ch, fg, bg))) in old.iter().zip(new).enumerate() {
+                        if *old == (ch, fg, bg) {
+                            continue;
+                        }
+                        cursor.move_to(buf, y, x as u16)?;
+                        pen.print(buf, ch, fg, bg)?;
+                        // The last column leaves the cursor waiting to wrap
+                        cursor.column = Some(x as u16 + 1).filter(|_| x + 1 < new.len());
+                    }
+                }
+                (old, Row::Text(text, color)) if old != new => {
+                    cursor.move_to(buf, y, 0)?;
+                    pen.print_line(buf, text, *color)?;
+                    cursor.column = None;
+                }
+                _ => {}
+            }
+        }
+
+        pen.reset(buf)?;
+        cursor.move_to(buf, rows.len() as u16, 0)?;
+        Ok(())
+    }
+}
+
+/// Where the cursor is within the area
+struct Cursor {
+    row: u16,
+    /// Unknown after printing text of unknown width
+    column: Option<u16>,
+}
+
+impl Cursor {
+    /// Move with the shortest relative sequence, or not at all
+    fn move_to(&mut self, buf: &mut Vec<u8>, row: u16, column: u16) -> Result<()> {
+        if row < self.row {
+            queue!(buf, cursor::MoveUp(self.row - row))?;
+        } else if row > self.row {
+            queue!(buf, cursor::MoveDown(row - self.row))?;
+        }
+
+        match self.column {
+            Some(current) if current == column => {}
+            _ if column == 0 => queue!(buf, Print('\r'))?,
+            Some(current) if current < column => queue!(buf, cursor::MoveRight(column - current))?,
+            _ => queue!(buf, cursor::MoveToColumn(column))?,
+        }
+
+        self.row = row;
+        self.column = Some(column);
+        Ok(())
+    }
+}
+
+/// The colors currently set, so runs of one color set them once
+#[derive(Default)]
+struct Pen {
+    fg: Option<Color>,
+    bg: Option<Color>,
+}
+
+impl Pen {
+    fn set(&mut self, buf: &mut Vec<u8>, fg: Option<Color>, bg: Option<Color>) -> Result<()> {
+        if (fg, bg) == (self.fg, self.bg) {
+            return Ok(());
+        }
+        if fg.is_none() && bg.is_none() {
+            queue!(buf, ResetColor)?;
+        } else {
+            // The 16 ANSI colors as their own codes: crossterm writes them as
+            // 256-color indices, which 16-color terminals may not know
+            if fg != self.fg {
+                let fg = fg.unwrap_or(Color::Reset);
+                match ansi_foreground(fg) {
+                    Some(code) => queue!(buf, Print(format_args!("\x1b[{}m", code)))?,
+                    None => queue!(buf, SetForegroundColor(fg))?,
+                }
+            }
+            if bg != self.bg {
+                let bg = bg.unwrap_or(Color::Reset);
+                match ansi_foreground(bg) {
+                    Some(code) => queue!(buf, Print(format_args!("\x1b[{}m", code + 10)))?,
+                    None => queue!(buf, SetBackgroundColor(bg))?,
+                }
+            }
+        }
+        self.fg = fg;
+        self.bg = bg;
+        Ok(())
+    }
+
+    fn print(&mut self, buf: &mut Vec<u8>, ch: char, fg: Option<Color>, bg: Option<Color>) -> Result<()> {
+        self.set(buf, fg, bg)?;
+        queue!(buf, Print(ch))?;
+        Ok(())
+    }
+
+    /// Replace the cursor's line with `text`
+    fn print_line(&mut self, buf: &mut Vec<u8>, text: &str, color: Option<Color>) -> Result<()> {
+        // Clearing fills the line with the background color
+        self.clear_background(buf)?;
+        queue!(buf, Clear(ClearType::CurrentLine))?;
+        if !text.is_empty() {
+            self.set(buf, color, None)?;
+            queue!(buf, Print(text))?;
+        }
+        Ok(())
+    }
+
+    /// Go back to the terminal's background color
+    fn clear_background(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        self.set(buf, self.fg, None)
+    }
+
+    /// Leave the terminal's colors as they were
+    fn reset(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        self.set(buf, None, None)
+    }
+}
+
+/// Get the SGR foreground code of one of the 16 ANSI colors (the background
+/// code is 10 more)
+fn ansi_foreground(color: Color) -> Option<u8> {
+    Some(match color {
+        Color::Black => 30,
+        Color::DarkRed => 31,
+        Color::DarkGreen => 32,
+        Color::DarkYellow => 33,
+        Color::DarkBlue => 34,
+        Color::DarkMagenta => 35,
+        Color::DarkCyan => 36,
+        Color::Grey => 37,
+        Color::DarkGrey => 90,
+        Color::Red => 91,
+        Color::Green => 92,
+        Color::Yellow => 93,
+        Color::Blue => 94,
+        Color::Magenta => 95,
+        Color::Cyan => 96,
+        Color::White => 97,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(renderer: &mut DiffRenderer, grid: &BrailleGrid) -> String {
+        let mut out = Vec::new();
+        renderer.render(&mut out, grid, None, grid.width() as u16).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_only_changed_cells_are_written() {
+        let mut renderer = DiffRenderer::new(2, ColorDepth::Monochrome);
+        let mut grid = BrailleGrid::new(3, 2);
+
+        assert_eq!(render(&mut renderer, &grid), "\x1b[2A\r⠀⠀⠀\r\n⠀⠀⠀\r\n");
+        assert_eq!(render(&mut renderer, &grid), "");
+
+        // Up to the top row, right to the changed cell, back below the area
+        grid.set_dot(2, 0);
+        assert_eq!(render(&mut renderer, &grid), "\x1b[2A\x1b[1C⠁\x1b[2B\r");
+
+        let mut out = Vec::new();
+        grid.clear();
+        let bytes = renderer.render(&mut out, &grid, None, 3).unwrap();
+        assert_eq!(bytes, out.len());
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let mut stats = RenderStats::default();
+        assert_eq!(stats.bytes_per_frame(), 0.0);
+        stats.record(100);
+        stats.record(20);
+        assert_eq!(stats.bytes, 120);
+        assert_eq!(stats.last_frame_bytes, 20);
+        assert_eq!(stats.bytes_per_frame(), 60.0);
+    }
+
+    #[test]
+    fn test_color_runs_set_once() {
+        let red = crate::braille::Color::new(255, 0, 0);
+        let mut renderer = DiffRenderer::new(0, ColorDepth::TrueColor);
+        let mut grid = BrailleGrid::new(3, 1);
+        for x in 0..3 {
+            grid.set_dot_with_color(x * 2, 0, red);
+        }
+
+        let output = render(&mut renderer, &grid);
+        assert_eq!(output.matches("\x1b[38;2;255;0;0m").count(), 1);
+        assert_eq!(output.matches("\x1b[0m").count(), 1);
+
+        // A row of changed cells is written without cursor moves in between
+        let mut grid = BrailleGrid::new(3, 1);
+        for x in 0..6 {
+            grid.set_dot_with_color(x, 1, red);
+        }
+        let output = render(&mut renderer, &grid);
+        assert_eq!(output, "\x1b[1A\x1b[38;2;255;0;0m⠒⠒⠒\x1b[0m\x1b[1B\r");
+
+        // Fewer colors redraw everything, with the nearest ANSI color
+        renderer.set_color_depth(ColorDepth::Ansi16);
+        let output = render(&mut renderer, &grid);
+        assert_eq!(output, "\x1b[1A\r\x1b[91m⠒⠒⠒\r\n\x1b[0m");
+    }
+
+    #[test]
+    fn test_backgrounds() {
+        use crate::braille::Color as Rgb;
+        let mut renderer = DiffRenderer::new(0, ColorDepth::Ansi16);
+        let mut grid = BrailleGrid::new(3, 1);
+        grid.set_background(0, 0, Rgb::BLUE);
+        grid.set_background(1, 0, Rgb::BLUE);
+        grid.set_dot_with_color(2, 0, Rgb::RED);
+
+        // The background is set once for its run, and the foreground on top
+        let output = render(&mut renderer, &grid);
+        assert_eq!(output, "\r\x1b[44m⠀\x1b[91m⠁\x1b[0m⠀\r\n");
+
+        // Dropping the background alone keeps the foreground
+        grid.clear();
+        grid.set_dot_with_color(0, 0, Rgb::RED);
+        grid.set_dot_with_color(2, 0, Rgb::RED);
+        grid.set_background(0, 0, Rgb::BLUE);
+        let output = render(&mut renderer, &grid);
+        assert_eq!(output, "\x1b[1A\x1b[91m\x1b[44m⠁\x1b[49m⠁\x1b[0m\x1b[1B\r");
+    }
+}
@@ -0,0 +1,112 @@
+// Frames recorded by a headless renderer
+//
+// With `RenderMode::Headless` nothing is written to the terminal; each frame
+// is kept in a `FrameBuffer` instead, so animations can be inspected frame
+// by frame in tests or recorded without a terminal.
+
+use super::{FrameSink, OutputPane};
+use crate::braille::BrailleGrid;
+use anyhow::Result;
+
+/// One rendered frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The animation as drawn
+    pub grid: BrailleGrid,
+    /// Text shown beneath it (e.g. output pane lines), one entry per line
+    pub text: Vec<String>,
+}
+
+impl Frame {
+    /// Capture a grid with text lines beneath it
+    pub fn new(grid: &BrailleGrid, text: Vec<String>) -> Self {
+        Self {
+            grid: grid.clone(),
+            text,
+        }
+    }
+
+    /// Get the grid's characters, one line per row
+    pub fn to_text(&self) -> String {
+        (0..self.grid.height())
+            .map(|y| (0..self.grid.width()).map(|x| self.grid.get_char(x, y)).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Check if no dot is set anywhere in the grid
+    pub fn is_blank(&self) -> bool {
+        (0..self.grid.height()).all(|y| (0..self.grid.width()).all(|x| self.grid.is_empty(x, y)))
+    }
+}
+
+/// In-memory frame sink, used for [`RenderMode::Headless`](super::RenderMode::Headless)
+#[derive(Debug, Clone, Default)]
+pub struct FrameBuffer {
+    width: u16,
+    height: u16,
+    frames: Vec<Frame>,
+}
+
+impl FrameBuffer {
+    /// Create an empty buffer for `width` × `height` cell frames
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Get the most recent frame
+    pub fn last(&self) -> Option<&Frame> {
+        self.frames.last()
+    }
+}
+
+impl FrameSink for FrameBuffer {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok((self.width, self.height))
+    }
+
+    fn render(&mut self, grid: &BrailleGrid) -> Result<()> {
+        self.frames.push(Frame::new(grid, Vec::new()));
+        Ok(())
+    }
+
+    fn render_with_pane(&mut self, grid: &BrailleGrid, pane: &OutputPane) -> Result<()> {
+        let text = pane.lines().map(|(text, _)| text.to_string()).collect();
+        self.frames.push(Frame::new(grid, text));
+        Ok(())
+    }
+
+    fn is_realtime(&self) -> bool {
+        false
+    }
+
+    fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    fn take_frames(&mut self) -> Vec<Frame> {
+        std::mem::take(&mut self.frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_text() {
+        let mut grid = BrailleGrid::new(2, 2);
+        let frame = Frame::new(&grid, Vec::new());
+        assert!(frame.is_blank());
+
+        grid.set_dot(0, 0);
+        let frame = Frame::new(&grid, vec!["done".to_string()]);
+        assert!(!frame.is_blank());
+        assert_eq!(frame.to_text(), "⠁⠀\n⠀⠀");
+        assert_eq!(frame.text, vec!["done"]);
+    }
+}
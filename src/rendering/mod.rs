@@ -1,10 +1,29 @@
 // Terminal rendering module
-use crate::braille::BrailleGrid;
-use anyhow::Result;
+mod color;
+mod diff;
+mod headless;
+mod sink;
+mod support;
+
+pub use color::ColorDepth;
+pub use diff::RenderStats;
+pub use headless::{Frame, FrameBuffer};
+pub use sink::{FrameSink, WriterSink};
+pub use support::{Passthrough, TerminalSupport, DISABLE_VAR};
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal, Write};
+use std::rc::Rc;
+
+use anyhow::{bail, Result};
 use crossterm::{
     cursor, execute, queue,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    style::{self, Print},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
 };
 use ratatui::{
     backend::CrosstermBackend,
@@ -14,35 +33,188 @@ use ratatui::{
     widgets::Paragraph,
     Terminal,
 };
-use std::io::{self, Stdout, Write};
+
+use crate::braille::{BrailleGrid, CellEncoding};
+use crate::signals::{self, InterruptGuard};
+use diff::DiffRenderer;
+
+/// Fail unless stdout is a terminal, before any escape codes are written
+pub(crate) fn require_terminal() -> Result<()> {
+    RenderTarget::Stdout.require_terminal()
+}
+
+/// Get a cell's character with its foreground and background colors as the
+/// terminal can show them
+///
+/// Without colors, half blocks drawn in two tones would lose their lower
+/// half, so cells are drawn by their dots alone.
+fn shown_cell(
+    grid: &BrailleGrid,
+    x: usize,
+    y: usize,
+    encoding: CellEncoding,
+    color_depth: ColorDepth,
+) -> (char, Option<style::Color>, Option<style::Color>) {
+    if color_depth == ColorDepth::Monochrome {
+        return (grid.get_char_as(x, y, encoding), None, None);
+    }
+    let glyph = grid.get_glyph(x, y, encoding);
+    let quantize = |color: Option<_>| color.and_then(|c| color_depth.quantize(c));
+    (glyph.ch, quantize(glyph.fg), quantize(glyph.bg))
+}
 
 /// Rendering mode for animations
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum RenderMode {
     /// Fullscreen mode - takes over entire terminal (for demos)
+    #[default]
     Fullscreen,
     /// Inline mode - renders in a fixed-height frame without clearing history
     Inline { height: u16 },
+    /// Headless mode - records frames in a [`FrameBuffer`] instead of drawing them
+    Headless { width: u16, height: u16 },
 }
 
-impl Default for RenderMode {
-    fn default() -> Self {
-        Self::Fullscreen
+/// Scrolling tail of command output shown beneath an animation
+///
+/// Keeps only the most recent `rows` lines, so memory stays bounded no
+/// matter how much output the command produces.
+#[derive(Debug, Clone)]
+pub struct OutputPane {
+    rows: u16,
+    lines: VecDeque<(String, Option<crate::braille::Color>)>,
+    overwrite_last: bool,
+}
+
+impl OutputPane {
+    /// Create an empty pane with the given height in terminal rows
+    pub fn new(rows: u16) -> Self {
+        Self {
+            rows,
+            lines: VecDeque::with_capacity(rows as usize),
+            overwrite_last: false,
+        }
+    }
+
+    /// Get the pane height in terminal rows
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    /// Append a line of output
+    ///
+    /// If the previous line was a progress update (`progress == true` when it
+    /// was pushed), the new line replaces it instead of scrolling.
+    pub fn push(&mut self, text: &str, color: Option<crate::braille::Color>, progress: bool) {
+        if self.overwrite_last {
+            self.lines.pop_back();
+        }
+        self.lines.push_back((text.to_string(), color));
+        while self.lines.len() > self.rows as usize {
+            self.lines.pop_front();
+        }
+        self.overwrite_last = progress;
+    }
+
+    /// Iterate over the visible lines, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = (&str, Option<crate::braille::Color>)> {
+        self.lines.iter().map(|(text, color)| (text.as_str(), *color))
+    }
+}
+
+/// Stream a [`TerminalRenderer`] draws on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderTarget {
+    #[default]
+    Stdout,
+    /// Keeps animations out of stdout, e.g. when it's piped elsewhere
+    Stderr,
+}
+
+impl RenderTarget {
+    /// Fail unless the stream is a terminal, before any escape codes are written
+    fn require_terminal(self) -> Result<()> {
+        let is_terminal = match self {
+            Self::Stdout => io::stdout().is_terminal(),
+            Self::Stderr => io::stderr().is_terminal(),
+        };
+        if !is_terminal {
+            bail!("Animations need a terminal, but {} is not one", self.name());
+        }
+        Ok(())
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
+
+    fn writer(self) -> TargetWriter {
+        TargetWriter {
+            target: self,
+            written: Rc::default(),
+        }
+    }
+}
+
+/// Writer for a render target, usable as a ratatui backend
+struct TargetWriter {
+    target: RenderTarget,
+    /// Bytes written through this writer, shared with whoever counts them
+    written: Rc<Cell<u64>>,
+}
+
+impl Write for TargetWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = match self.target {
+            RenderTarget::Stdout => io::stdout().write(buf),
+            RenderTarget::Stderr => io::stderr().write(buf),
+        }?;
+        self.written.set(self.written.get() + written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.target {
+            RenderTarget::Stdout => io::stdout().flush(),
+            RenderTarget::Stderr => io::stderr().flush(),
+        }
     }
 }
 
 /// Terminal renderer with panic-safe cleanup
+///
+/// In inline mode the cursor rests on the line below the animation area
+/// between frames, and each frame moves up and rewrites only the cells that
+/// changed. Only relative movement is used, so no cursor position query is
+/// written to the terminal (crossterm would send it to stdout, whatever the
+/// target).
 pub struct TerminalRenderer {
-    terminal: Option<Terminal<CrosstermBackend<Stdout>>>,
+    terminal: Option<Terminal<CrosstermBackend<TargetWriter>>>,
     mode: RenderMode,
-    inline_start_row: u16,
+    target: RenderTarget,
+    /// Colors the terminal can show
+    color_depth: ColorDepth,
+    /// Characters cells are drawn with
+    encoding: CellEncoding,
+    /// Previous inline frame, to draw only what changed
+    diff: DiffRenderer,
+    /// Bytes ratatui has written in fullscreen mode
+    written: Rc<Cell<u64>>,
+    stats: RenderStats,
     _cleanup: TerminalCleanup,
 }
 
 /// RAII guard for terminal cleanup
+///
+/// Termination signals are deferred while it is alive, so the terminal is
+/// always restored before crabcrust exits.
 struct TerminalCleanup {
     mode: RenderMode,
-    inline_start_row: u16,
+    target: RenderTarget,
+    _interrupts: InterruptGuard,
 }
 
 impl Drop for TerminalCleanup {
@@ -51,39 +223,31 @@ impl Drop for TerminalCleanup {
             RenderMode::Fullscreen => {
                 let _ = disable_raw_mode();
                 let _ = execute!(
-                    io::stdout(),
+                    self.target.writer(),
                     LeaveAlternateScreen,
                     cursor::Show
                 );
             }
             RenderMode::Inline { height } => {
-                // Clear the inline animation area
-                let _ = Self::clear_inline_area(self.inline_start_row, height);
-                let _ = execute!(io::stdout(), cursor::Show);
+                // Clear the inline animation area, leaving the cursor at its
+                // top: this is where new content (like command output) goes
+                let mut out = self.target.writer();
+                let _ = Self::clear_inline_area(&mut out, height);
+                let _ = execute!(out, cursor::MoveUp(height), cursor::Show);
             }
+            RenderMode::Headless { .. } => {}
         }
     }
 }
 
 impl TerminalCleanup {
-    /// Clear the inline animation area
-    fn clear_inline_area(start_row: u16, height: u16) -> Result<()> {
-        let mut stdout = io::stdout();
-
-        // Move to start of animation area
-        execute!(stdout, cursor::MoveTo(0, start_row))?;
-
-        // Clear each line by overwriting with spaces, then move down
-        for i in 0..height {
-            execute!(stdout, cursor::MoveTo(0, start_row + i))?;
-            queue!(stdout, crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine))?;
+    /// Clear the inline animation area, from the line below it
+    fn clear_inline_area(out: &mut TargetWriter, height: u16) -> Result<()> {
+        queue!(out, cursor::MoveUp(height), cursor::MoveToColumn(0))?;
+        for _ in 0..height {
+            queue!(out, Clear(ClearType::CurrentLine), Print("\r\n"))?;
         }
-
-        // Move cursor back to the start position
-        // This is where new content should appear (like command output)
-        execute!(stdout, cursor::MoveTo(0, start_row))?;
-
-        stdout.flush()?;
+        out.flush()?;
         Ok(())
     }
 }
@@ -94,52 +258,65 @@ impl TerminalRenderer {
         Self::with_mode(RenderMode::Fullscreen)
     }
 
-    /// Create a terminal renderer with specified mode
+    /// Create a terminal renderer with specified mode, drawing on stdout
+    ///
+    /// Fails if stdout isn't a terminal.
     pub fn with_mode(mode: RenderMode) -> Result<Self> {
-        match mode {
+        Self::with_target(mode, RenderTarget::Stdout)
+    }
+
+    /// Create a terminal renderer with specified mode, drawing on `target`
+    ///
+    /// Fails if `target` isn't a terminal. Headless mode has no terminal at
+    /// all; it renders into a [`FrameBuffer`] instead.
+    pub fn with_target(mode: RenderMode, target: RenderTarget) -> Result<Self> {
+        if let RenderMode::Headless { .. } = mode {
+            bail!("Headless mode doesn't use a terminal; render into a FrameBuffer instead");
+        }
+        target.require_terminal()?;
+        let interrupts = signals::defer_interrupts()?;
+        let color_depth = TerminalSupport::detect().color_depth;
+        let mut out = target.writer();
+        let written = out.written.clone();
+        let mut reserved = 0;
+
+        let terminal = match mode {
             RenderMode::Fullscreen => {
                 enable_raw_mode()?;
-                execute!(io::stdout(), EnterAlternateScreen, cursor::Hide)?;
-
-                let stdout = io::stdout();
-                let backend = CrosstermBackend::new(stdout);
-                let terminal = Terminal::new(backend)?;
-
-                Ok(Self {
-                    terminal: Some(terminal),
-                    mode,
-                    inline_start_row: 0,
-                    _cleanup: TerminalCleanup { mode, inline_start_row: 0 },
-                })
+                execute!(out, EnterAlternateScreen, cursor::Hide)?;
+                Some(Terminal::new(CrosstermBackend::new(out))?)
             }
             RenderMode::Inline { height } => {
                 // For inline mode, don't use ratatui's Terminal at all
-                // Just reserve space in the current terminal
-                let mut stdout = io::stdout();
-
-                // Print empty lines to reserve space
-                // This will cause scrolling if we're near the bottom
+                // Print empty lines to reserve space; this scrolls if we're
+                // near the bottom, and leaves the cursor right below the area
                 for _ in 0..height {
-                    writeln!(stdout)?;
+                    queue!(out, Print("\r\n"))?;
                 }
-
-                // Move cursor back up to the start of the animation area
-                // This ensures the animation is visible even if we started at the bottom
-                execute!(stdout, cursor::MoveUp(height))?;
-
-                // Get the position - this is where the animation will render
-                let (_, animation_start_row) = cursor::position()?;
-
-                stdout.flush()?;
-
-                Ok(Self {
-                    terminal: None,
-                    mode,
-                    inline_start_row: animation_start_row,
-                    _cleanup: TerminalCleanup { mode, inline_start_row: animation_start_row },
-                })
+                out.flush()?;
+                reserved = height;
+                None
             }
-        }
+            RenderMode::Headless { .. } => unreachable!("rejected above"),
+        };
+        // Setup isn't part of any frame
+        written.set(0);
+
+        Ok(Self {
+            terminal,
+            mode,
+            target,
+            color_depth,
+            encoding: CellEncoding::default(),
+            diff: DiffRenderer::new(reserved, color_depth),
+            written,
+            stats: RenderStats::default(),
+            _cleanup: TerminalCleanup {
+                mode,
+                target,
+                _interrupts: interrupts,
+            },
+        })
     }
 
     /// Get the rendering mode
@@ -147,6 +324,16 @@ impl TerminalRenderer {
         self.mode
     }
 
+    /// Get the stream the renderer draws on
+    pub fn target(&self) -> RenderTarget {
+        self.target
+    }
+
+    /// Get the bytes written for the frames drawn so far
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
     /// Get terminal size (width, height)
     pub fn size(&self) -> Result<(u16, u16)> {
         match self.mode {
@@ -158,6 +345,7 @@ impl TerminalRenderer {
                 let (width, _) = crossterm::terminal::size()?;
                 Ok((width, height))
             }
+            RenderMode::Headless { width, height } => Ok((width, height)),
         }
     }
 
@@ -170,23 +358,68 @@ impl TerminalRenderer {
     }
 
     /// Manually clear the inline animation area (usually automatic on drop)
-    pub fn clear_inline_frame(&self) -> Result<()> {
+    ///
+    /// The area stays reserved, and the next frame is drawn in full.
+    pub fn clear_inline_frame(&mut self) -> Result<()> {
         if let RenderMode::Inline { height } = self.mode {
-            TerminalCleanup::clear_inline_area(self.inline_start_row, height)?;
+            TerminalCleanup::clear_inline_area(&mut self.target.writer(), height)?;
+            self.diff.invalidate();
         }
         Ok(())
     }
 
+    /// Count the bytes ratatui wrote for a fullscreen frame
+    fn record_fullscreen_frame(&mut self) {
+        if self.terminal.is_some() {
+            let written = self.written.get();
+            self.stats.record((written - self.stats.bytes) as usize);
+        }
+    }
+
+    /// Draw colors at a different depth than detected
+    pub fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        self.color_depth = color_depth;
+        self.diff.set_color_depth(color_depth);
+    }
+
+    /// Draw cells with other characters than Braille
+    pub fn set_cell_encoding(&mut self, encoding: CellEncoding) {
+        self.encoding = encoding;
+        self.diff.set_cell_encoding(encoding);
+    }
+
+    /// Get a cell's character and style, with its colors as the terminal
+    /// can show them
+    fn span_at(
+        color_depth: ColorDepth,
+        encoding: CellEncoding,
+        grid: &BrailleGrid,
+        x: usize,
+        y: usize,
+    ) -> Span<'static> {
+        let (ch, fg, bg) = shown_cell(grid, x, y, encoding, color_depth);
+        let mut style = Style::default();
+        if let Some(fg) = fg {
+            style = style.fg(fg.into());
+        }
+        if let Some(bg) = bg {
+            style = style.bg(bg.into());
+        }
+        Span::styled(ch.to_string(), style)
+    }
+
     /// Render a BrailleGrid to the terminal
     pub fn render_braille(&mut self, grid: &BrailleGrid) -> Result<()> {
         match self.mode {
-            RenderMode::Fullscreen => self.render_braille_fullscreen(grid),
-            RenderMode::Inline { .. } => self.render_braille_inline(grid),
+            RenderMode::Inline { .. } => self.render_inline(grid, None),
+            _ => self.render_braille_fullscreen(grid),
         }
     }
 
     /// Render braille in fullscreen mode (using ratatui)
     fn render_braille_fullscreen(&mut self, grid: &BrailleGrid) -> Result<()> {
+        let color_depth = self.color_depth;
+        let encoding = self.encoding;
         let terminal = self.terminal.as_mut().unwrap();
         terminal.draw(|frame| {
             let area = frame.area();
@@ -196,16 +429,7 @@ impl TerminalRenderer {
             for y in 0..grid.height().min(area.height as usize) {
                 let mut spans = Vec::new();
                 for x in 0..grid.width().min(area.width as usize) {
-                    let ch = grid.get_char(x, y);
-                    let color = grid.get_color(x, y);
-
-                    let style = if let Some(c) = color {
-                        Style::default().fg(ratatui::style::Color::Rgb(c.r, c.g, c.b))
-                    } else {
-                        Style::default()
-                    };
-
-                    spans.push(Span::styled(ch.to_string(), style));
+                    spans.push(Self::span_at(color_depth, encoding, grid, x, y));
                 }
                 lines.push(Line::from(spans));
             }
@@ -214,50 +438,29 @@ impl TerminalRenderer {
             frame.render_widget(paragraph, area);
         })?;
 
+        self.record_fullscreen_frame();
         Ok(())
     }
 
-    /// Render braille in inline mode (direct stdout writing)
-    fn render_braille_inline(&mut self, grid: &BrailleGrid) -> Result<()> {
-        let mut stdout = io::stdout();
-
-        // Move to start position
-        execute!(stdout, cursor::MoveTo(0, self.inline_start_row))?;
-
-        // Render each line of the grid directly
-        for y in 0..grid.height() {
-            // Position at the start of the line
-            queue!(stdout, cursor::MoveTo(0, self.inline_start_row + y as u16))?;
-
-            for x in 0..grid.width() {
-                let ch = grid.get_char(x, y);
-                let color = grid.get_color(x, y);
-
-                if let Some(c) = color {
-                    queue!(
-                        stdout,
-                        SetForegroundColor(Color::Rgb {
-                            r: c.r,
-                            g: c.g,
-                            b: c.b
-                        }),
-                        Print(ch),
-                        ResetColor
-                    )?;
-                } else {
-                    queue!(stdout, Print(ch))?;
-                }
-            }
-        }
-
-        // Move cursor to after the animation area
-        let (_, height) = self.size()?;
-        execute!(stdout, cursor::MoveTo(0, self.inline_start_row + height))?;
-
-        stdout.flush()?;
+    /// Render in inline mode, over the previous frame
+    fn render_inline(&mut self, grid: &BrailleGrid, pane: Option<&OutputPane>) -> Result<()> {
+        let (width, _) = self.size()?;
+        let bytes = self.diff.render(&mut self.target.writer(), grid, pane, width)?;
+        self.stats.record(bytes);
         Ok(())
     }
 
+    /// Render a BrailleGrid with an output pane beneath it
+    ///
+    /// The grid should be `pane.rows()` shorter than the renderer so both fit.
+    pub fn render_braille_with_pane(&mut self, grid: &BrailleGrid, pane: &OutputPane) -> Result<()> {
+        if self.mode == RenderMode::Fullscreen {
+            let text: Vec<&str> = pane.lines().map(|(text, _)| text).collect();
+            return self.render_braille_with_text(grid, &text.join("\n"));
+        }
+        self.render_inline(grid, Some(pane))
+    }
+
     /// Render text lines to the terminal (fullscreen mode only)
     pub fn render_text(&mut self, text: &str) -> Result<()> {
         if let Some(terminal) = &mut self.terminal {
@@ -267,11 +470,14 @@ impl TerminalRenderer {
                 frame.render_widget(paragraph, area);
             })?;
         }
+        self.record_fullscreen_frame();
         Ok(())
     }
 
     /// Render BrailleGrid with text below it (fullscreen mode only)
     pub fn render_braille_with_text(&mut self, grid: &BrailleGrid, text: &str) -> Result<()> {
+        let color_depth = self.color_depth;
+        let encoding = self.encoding;
         if let Some(terminal) = &mut self.terminal {
             terminal.draw(|frame| {
             let area = frame.area();
@@ -292,16 +498,7 @@ impl TerminalRenderer {
             for y in 0..grid.height().min(braille_height as usize) {
                 let mut spans = Vec::new();
                 for x in 0..grid.width().min(area.width as usize) {
-                    let ch = grid.get_char(x, y);
-                    let color = grid.get_color(x, y);
-
-                    let style = if let Some(c) = color {
-                        Style::default().fg(ratatui::style::Color::Rgb(c.r, c.g, c.b))
-                    } else {
-                        Style::default()
-                    };
-
-                    spans.push(Span::styled(ch.to_string(), style));
+                    spans.push(Self::span_at(color_depth, encoding, grid, x, y));
                 }
                 braille_lines.push(Line::from(spans));
             }
@@ -322,12 +519,66 @@ impl TerminalRenderer {
             })?;
         }
 
+        self.record_fullscreen_frame();
         Ok(())
     }
 }
 
+impl FrameSink for TerminalRenderer {
+    fn size(&self) -> Result<(u16, u16)> {
+        TerminalRenderer::size(self)
+    }
+
+    fn render(&mut self, grid: &BrailleGrid) -> Result<()> {
+        self.render_braille(grid)
+    }
+
+    fn render_with_pane(&mut self, grid: &BrailleGrid, pane: &OutputPane) -> Result<()> {
+        self.render_braille_with_pane(grid, pane)
+    }
+
+    fn stats(&self) -> Option<RenderStats> {
+        Some(self.stats)
+    }
+
+    fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        TerminalRenderer::set_color_depth(self, color_depth)
+    }
+
+    fn set_cell_encoding(&mut self, encoding: CellEncoding) {
+        TerminalRenderer::set_cell_encoding(self, encoding)
+    }
+}
+
 impl Default for TerminalRenderer {
     fn default() -> Self {
         Self::new().expect("Failed to create terminal renderer")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_pane_scrolls() {
+        let mut pane = OutputPane::new(2);
+        pane.push("one", None, false);
+        pane.push("two", None, false);
+        pane.push("three", None, false);
+
+        let lines: Vec<&str> = pane.lines().map(|(text, _)| text).collect();
+        assert_eq!(lines, vec!["two", "three"]);
+    }
+
+    #[test]
+    fn test_output_pane_progress_overwrites() {
+        let mut pane = OutputPane::new(3);
+        pane.push("Counting objects: 10%", None, true);
+        pane.push("Counting objects: 100%", None, false);
+        pane.push("done", None, false);
+
+        let lines: Vec<&str> = pane.lines().map(|(text, _)| text).collect();
+        assert_eq!(lines, vec!["Counting objects: 100%", "done"]);
+    }
+}
@@ -0,0 +1,220 @@
+// Output targets animation frames are rendered through
+//
+// `AnimationPlayer` draws every frame into a `FrameSink`. The terminal
+// renderer (on stdout or stderr) is the usual one; `WriterSink` draws into
+// any `Write` with relative cursor movement, and `FrameBuffer` keeps frames
+// in memory.
+
+use super::diff::{DiffRenderer, RenderStats};
+use super::{ColorDepth, Frame, OutputPane};
+use crate::braille::{BrailleGrid, CellEncoding};
+use anyhow::Result;
+use std::io::Write;
+
+/// Something animation frames can be drawn into
+pub trait FrameSink {
+    /// Get the size of the drawing area in cells (width, height)
+    fn size(&self) -> Result<(u16, u16)>;
+
+    /// Draw a frame
+    fn render(&mut self, grid: &BrailleGrid) -> Result<()>;
+
+    /// Draw a frame with an output pane beneath it
+    ///
+    /// The grid is `pane.rows()` shorter than [`size`](Self::size). Sinks
+    /// without room for text may leave the pane out, which is the default.
+    fn render_with_pane(&mut self, grid: &BrailleGrid, pane: &OutputPane) -> Result<()> {
+        let _ = pane;
+        self.render(grid)
+    }
+
+    /// Check if frames are shown as they're drawn
+    ///
+    /// Playback into sinks that only record frames uses simulated time
+    /// instead of pacing frames in real time.
+    fn is_realtime(&self) -> bool {
+        true
+    }
+
+    /// Get the frames recorded so far, for sinks that record them
+    fn frames(&self) -> &[Frame] {
+        &[]
+    }
+
+    /// Take the frames recorded so far, leaving none
+    fn take_frames(&mut self) -> Vec<Frame> {
+        Vec::new()
+    }
+
+    /// Get the bytes written so far, for sinks that write them
+    fn stats(&self) -> Option<RenderStats> {
+        None
+    }
+
+    /// Draw colors at the given depth, for sinks that draw colors
+    fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        let _ = color_depth;
+    }
+
+    /// Draw cells with the given characters, for sinks that draw characters
+    fn set_cell_encoding(&mut self, encoding: CellEncoding) {
+        let _ = encoding;
+    }
+}
+
+impl<S: FrameSink + ?Sized> FrameSink for Box<S> {
+    fn size(&self) -> Result<(u16, u16)> {
+        (**self).size()
+    }
+
+    fn render(&mut self, grid: &BrailleGrid) -> Result<()> {
+        (**self).render(grid)
+    }
+
+    fn render_with_pane(&mut self, grid: &BrailleGrid, pane: &OutputPane) -> Result<()> {
+        (**self).render_with_pane(grid, pane)
+    }
+
+    fn is_realtime(&self) -> bool {
+        (**self).is_realtime()
+    }
+
+    fn frames(&self) -> &[Frame] {
+        (**self).frames()
+    }
+
+    fn take_frames(&mut self) -> Vec<Frame> {
+        (**self).take_frames()
+    }
+
+    fn stats(&self) -> Option<RenderStats> {
+        (**self).stats()
+    }
+
+    fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        (**self).set_color_depth(color_depth)
+    }
+
+    fn set_cell_encoding(&mut self, encoding: CellEncoding) {
+        (**self).set_cell_encoding(encoding)
+    }
+}
+
+/// Frames drawn as ANSI text into any writer
+///
+/// The first frame is written row by row; later frames move the cursor back
+/// up and overwrite only the cells that changed, so a terminal stream shows
+/// them in place. No terminal queries are made, which makes it safe for
+/// embedding in other tools or for recording to a file.
+pub struct WriterSink<W: Write> {
+    writer: W,
+    width: u16,
+    height: u16,
+    diff: DiffRenderer,
+    stats: RenderStats,
+}
+
+impl<W: Write> WriterSink<W> {
+    /// Create a sink drawing `width` × `height` cell frames into `writer`
+    pub fn new(writer: W, width: u16, height: u16) -> Self {
+        Self {
+            writer,
+            width,
+            height,
+            diff: DiffRenderer::new(0, ColorDepth::TrueColor),
+            stats: RenderStats::default(),
+        }
+    }
+
+    /// Draw colors (default) or plain characters only
+    pub fn with_color(self, color: bool) -> Self {
+        let color_depth = if color {
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Monochrome
+        };
+        self.with_color_depth(color_depth)
+    }
+
+    /// Draw colors at the given depth (default: 24-bit)
+    pub fn with_color_depth(mut self, color_depth: ColorDepth) -> Self {
+        self.diff.set_color_depth(color_depth);
+        self
+    }
+
+    /// Draw cells with the given characters (default: Braille)
+    pub fn with_cell_encoding(mut self, encoding: CellEncoding) -> Self {
+        self.diff.set_cell_encoding(encoding);
+        self
+    }
+
+    /// Get the writer back
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_frame(&mut self, grid: &BrailleGrid, pane: Option<&OutputPane>) -> Result<()> {
+        let bytes = self.diff.render(&mut self.writer, grid, pane, self.width)?;
+        self.stats.record(bytes);
+        Ok(())
+    }
+}
+
+impl<W: Write> FrameSink for WriterSink<W> {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok((self.width, self.height))
+    }
+
+    fn render(&mut self, grid: &BrailleGrid) -> Result<()> {
+        self.write_frame(grid, None)
+    }
+
+    fn render_with_pane(&mut self, grid: &BrailleGrid, pane: &OutputPane) -> Result<()> {
+        self.write_frame(grid, Some(pane))
+    }
+
+    fn stats(&self) -> Option<RenderStats> {
+        Some(self.stats)
+    }
+
+    fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        self.diff.set_color_depth(color_depth)
+    }
+
+    fn set_cell_encoding(&mut self, encoding: CellEncoding) {
+        self.diff.set_cell_encoding(encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer_sink_redraws_in_place() {
+        let mut sink = WriterSink::new(Vec::new(), 2, 1).with_color(false);
+        let mut grid = BrailleGrid::new(2, 1);
+        grid.set_dot(0, 0);
+
+        sink.render(&grid).unwrap();
+        grid.set_dot(2, 0);
+        sink.render(&grid).unwrap();
+        assert_eq!(sink.stats().unwrap().frames, 2);
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        // Second frame moves up one row and over to the changed cell
+        assert_eq!(output, "\r⠁⠀\r\n\x1b[1A\x1b[1C⠁\x1b[1B\r");
+    }
+
+    #[test]
+    fn test_writer_sink_pane() {
+        let mut sink = WriterSink::new(Vec::new(), 4, 2);
+        let mut pane = OutputPane::new(1);
+        pane.push("hello\tworld", None, false);
+
+        sink.render_with_pane(&BrailleGrid::new(4, 1), &pane).unwrap();
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        assert!(output.ends_with("\x1b[2Khell\r\n"));
+    }
+}
@@ -0,0 +1,115 @@
+// What the environment lets crabcrust draw
+//
+// Animations need a real terminal on stdout. Pipes, CI logs, dumb terminals
+// and an explicit opt-out get plain passthrough instead, and NO_COLOR keeps
+// animations but drops their colors.
+
+use super::ColorDepth;
+use std::io::IsTerminal;
+
+/// Set to a non-empty value to run wrapped commands without animations
+pub const DISABLE_VAR: &str = "CRABCRUST_DISABLE";
+
+/// Why wrapped commands run without animations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Passthrough {
+    /// [`DISABLE_VAR`] is set
+    Requested,
+    /// stdout is a pipe or file
+    NotATerminal,
+    /// `CI` is set, as by most CI services
+    Ci,
+    /// `TERM=dumb`: no cursor movement
+    DumbTerminal,
+}
+
+/// Terminal capabilities detected from stdout and the environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSupport {
+    /// Why animations are off, if they are
+    pub passthrough: Option<Passthrough>,
+    /// Colors the terminal can show (none with `NO_COLOR`)
+    pub color_depth: ColorDepth,
+}
+
+impl TerminalSupport {
+    /// Detect support for the current process
+    pub fn detect() -> Self {
+        Self::from_env(std::io::stdout().is_terminal(), |key| std::env::var(key).ok())
+    }
+
+    /// Detect support from whether stdout is a terminal and a variable lookup
+    pub fn from_env<F>(stdout_is_terminal: bool, var: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let is_set = |key: &str| var(key).is_some_and(|value| !value.is_empty());
+
+        let passthrough = if is_set(DISABLE_VAR) {
+            Some(Passthrough::Requested)
+        } else if !stdout_is_terminal {
+            Some(Passthrough::NotATerminal)
+        } else if var("CI").is_some_and(|value| !matches!(value.as_str(), "" | "0" | "false")) {
+            Some(Passthrough::Ci)
+        } else if var("TERM").as_deref() == Some("dumb") {
+            Some(Passthrough::DumbTerminal)
+        } else {
+            None
+        };
+
+        // https://no-color.org: any non-empty value
+        let color_depth = if is_set("NO_COLOR") {
+            ColorDepth::Monochrome
+        } else {
+            ColorDepth::from_env(&var)
+        };
+
+        Self {
+            passthrough,
+            color_depth,
+        }
+    }
+
+    /// Check if animations can be shown
+    pub fn animations_enabled(&self) -> bool {
+        self.passthrough.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(is_terminal: bool, vars: &[(&str, &str)]) -> TerminalSupport {
+        TerminalSupport::from_env(is_terminal, |key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_detect_passthrough() {
+        assert!(detect(true, &[("TERM", "xterm-256color")]).animations_enabled());
+        assert_eq!(detect(false, &[]).passthrough, Some(Passthrough::NotATerminal));
+        assert_eq!(detect(true, &[("CI", "true")]).passthrough, Some(Passthrough::Ci));
+        assert_eq!(detect(true, &[("TERM", "dumb")]).passthrough, Some(Passthrough::DumbTerminal));
+        assert_eq!(
+            detect(false, &[("CRABCRUST_DISABLE", "1")]).passthrough,
+            Some(Passthrough::Requested)
+        );
+
+        // Empty or explicitly false values don't count
+        assert!(detect(true, &[("CI", "false"), ("CRABCRUST_DISABLE", "")]).animations_enabled());
+    }
+
+    #[test]
+    fn test_detect_no_color() {
+        let truecolor = [("COLORTERM", "truecolor")];
+        assert_eq!(detect(true, &truecolor).color_depth, ColorDepth::TrueColor);
+        assert_eq!(detect(true, &[truecolor[0], ("NO_COLOR", "1")]).color_depth, ColorDepth::Monochrome);
+        assert_eq!(detect(true, &[truecolor[0], ("NO_COLOR", "")]).color_depth, ColorDepth::TrueColor);
+        // NO_COLOR drops colors but keeps animations
+        assert!(detect(true, &[("NO_COLOR", "1")]).animations_enabled());
+    }
+}
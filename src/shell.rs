@@ -0,0 +1,256 @@
+// Shell integration printed by `crabcrust init <shell>`
+//
+// The script defines `git` and `cargo` functions that hand the subcommands
+// crabcrust animates to it and run everything else directly. Nothing is
+// defined in non-interactive shells, and the functions fall back to the real
+// commands when output isn't a terminal or DISABLE_VAR is set, so scripts
+// and pipes are never affected.
+
+use crate::rendering::DISABLE_VAR;
+use crate::wrapper::{cargo, git};
+use anyhow::{bail, Result};
+use std::fmt;
+
+/// Shells `crabcrust init` can set up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    /// Get the integration script, calling crabcrust at `program`
+    pub fn script(self, program: &str) -> String {
+        let template = match self {
+            Self::Bash | Self::Zsh => POSIX_TEMPLATE,
+            Self::Fish => FISH_TEMPLATE,
+        };
+        let (separator, quoted) = match self {
+            Self::Bash | Self::Zsh => ("|", quote_posix(program)),
+            Self::Fish => (" ", quote_fish(program)),
+        };
+
+        template
+            .replace("{shell}", &self.to_string())
+            .replace("{rc_file}", self.rc_file())
+            .replace("{disable}", DISABLE_VAR)
+            .replace("{program}", &quoted)
+            .replace("{git_value_options}", &git::VALUE_OPTIONS.join(separator))
+            .replace("{git_subcommands}", &git::SUBCOMMANDS.join(separator))
+            .replace("{cargo_value_options}", &cargo::VALUE_OPTIONS.join(separator))
+            .replace("{cargo_subcommands}", &cargo::SUBCOMMANDS.join(separator))
+    }
+
+    /// Startup file the script is loaded from, with the line to add
+    fn rc_file(self) -> &'static str {
+        match self {
+            Self::Bash => "~/.bashrc:\n#     eval \"$(crabcrust init bash)\"",
+            Self::Zsh => "~/.zshrc:\n#     eval \"$(crabcrust init zsh)\"",
+            Self::Fish => "~/.config/fish/config.fish:\n#     crabcrust init fish | source",
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        })
+    }
+}
+
+impl std::str::FromStr for Shell {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            _ => bail!("Unsupported shell '{}' (expected bash, zsh or fish)", s),
+        }
+    }
+}
+
+/// Quote a word for bash and zsh
+fn quote_posix(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+/// Quote a word for fish
+fn quote_fish(word: &str) -> String {
+    format!("'{}'", word.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+const POSIX_TEMPLATE: &str = r#"# crabcrust shell integration for {shell}
+#
+# Add to {rc_file}
+#
+# Set {disable}=1 to run git and cargo without animations.
+
+if [[ $- == *i* ]]; then
+
+__crabcrust_active() {
+    [[ -z "${{disable}:-}" && $- == *i* && -t 1 ]]
+}
+
+__crabcrust_git_subcommand() {
+    while [[ $# -gt 0 ]]; do
+        case "$1" in
+            {git_value_options})
+                shift
+                [[ $# -gt 0 ]] && shift
+                ;;
+            -*) shift ;;
+            *)
+                printf '%s' "$1"
+                return
+                ;;
+        esac
+    done
+}
+
+__crabcrust_cargo_subcommand() {
+    while [[ $# -gt 0 ]]; do
+        case "$1" in
+            {cargo_value_options})
+                shift
+                [[ $# -gt 0 ]] && shift
+                ;;
+            -*|+*) shift ;;
+            *)
+                printf '%s' "$1"
+                return
+                ;;
+        esac
+    done
+}
+
+function git {
+    if __crabcrust_active; then
+        case "$(__crabcrust_git_subcommand "$@")" in
+            {git_subcommands})
+                command {program} git "$@"
+                return
+                ;;
+        esac
+    fi
+    command git "$@"
+}
+
+function cargo {
+    if __crabcrust_active; then
+        case "$(__crabcrust_cargo_subcommand "$@")" in
+            {cargo_subcommands})
+                command {program} cargo "$@"
+                return
+                ;;
+        esac
+    fi
+    command cargo "$@"
+}
+
+fi
+"#;
+
+const FISH_TEMPLATE: &str = r#"# crabcrust shell integration for {shell}
+#
+# Add to {rc_file}
+#
+# Set {disable}=1 to run git and cargo without animations.
+
+if status is-interactive
+
+function __crabcrust_active
+    test -z "${disable}"; and status is-interactive; and test -t 1
+end
+
+function __crabcrust_git_subcommand
+    while set -q argv[1]
+        switch $argv[1]
+            case {git_value_options}
+                set -e argv[1]
+                set -q argv[1]; and set -e argv[1]
+            case '-*'
+                set -e argv[1]
+            case '*'
+                echo $argv[1]
+                return
+        end
+    end
+end
+
+function __crabcrust_cargo_subcommand
+    while set -q argv[1]
+        switch $argv[1]
+            case {cargo_value_options}
+                set -e argv[1]
+                set -q argv[1]; and set -e argv[1]
+            case '-*' '+*'
+                set -e argv[1]
+            case '*'
+                echo $argv[1]
+                return
+        end
+    end
+end
+
+function git --wraps git --description 'git with crabcrust animations'
+    set -l subcommand (__crabcrust_git_subcommand $argv)
+    if __crabcrust_active; and contains -- "$subcommand" {git_subcommands}
+        command {program} git $argv
+    else
+        command git $argv
+    end
+end
+
+function cargo --wraps cargo --description 'cargo with crabcrust animations'
+    set -l subcommand (__crabcrust_cargo_subcommand $argv)
+    if __crabcrust_active; and contains -- "$subcommand" {cargo_subcommands}
+        command {program} cargo $argv
+    else
+        command cargo $argv
+    end
+end
+
+end
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shell() {
+        assert_eq!("bash".parse::<Shell>().unwrap(), Shell::Bash);
+        assert_eq!("ZSH".parse::<Shell>().unwrap(), Shell::Zsh);
+        assert_eq!("fish".parse::<Shell>().unwrap(), Shell::Fish);
+        assert!("tcsh".parse::<Shell>().is_err());
+    }
+
+    #[test]
+    fn test_script_placeholders() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = shell.script("/usr/bin/crabcrust");
+            assert!(!script.contains("{git_"), "{}", shell);
+            assert!(!script.contains("{cargo_"), "{}", shell);
+            assert!(script.contains("CRABCRUST_DISABLE"));
+            assert!(script.contains("'/usr/bin/crabcrust' git"));
+        }
+
+        let script = Shell::Bash.script("crabcrust");
+        assert!(script.contains("commit|push|pull"));
+        assert!(script.contains("-C|-c|--git-dir"));
+        assert!(script.contains("-C|--color|--config"));
+        assert!(script.contains("${CRABCRUST_DISABLE:-}"));
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote_posix("/opt/it's/crabcrust"), r"'/opt/it'\''s/crabcrust'");
+        assert_eq!(quote_fish(r"C:\it's"), r"'C:\\it\'s'");
+    }
+}
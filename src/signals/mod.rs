@@ -0,0 +1,283 @@
+// Signal handling for wrapped commands and the terminal renderer
+//
+// One process-wide handler is installed for SIGINT/SIGTERM/SIGHUP/SIGQUIT
+// and SIGWINCH. Depending on what is active it:
+//   - forwards the signal to the wrapped child process,
+//   - defers it so the renderer can restore the terminal before exiting, or
+//   - falls back to the default action (usually terminating crabcrust).
+
+use thiserror::Error;
+
+#[cfg(unix)]
+pub use unix::{defer_interrupts, forward_to_child, pending};
+#[cfg(unix)]
+pub(crate) use unix::copy_window_size;
+
+#[cfg(not(unix))]
+pub use fallback::{defer_interrupts, forward_to_child, pending};
+
+/// Returned by animation loops when a termination signal arrives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("interrupted by signal {signal}")]
+pub struct Interrupted {
+    pub signal: i32,
+}
+
+impl Interrupted {
+    /// Shell-style exit code for the signal (128 + signal number)
+    pub fn exit_code(&self) -> i32 {
+        128 + self.signal
+    }
+}
+
+/// Forwards signals to a child process until dropped
+///
+/// A forwarded signal is only reported by [`pending`] if the child died from
+/// it (see [`ChildGuard::child_exited`]); children like `less` or a REPL
+/// handle Ctrl-C themselves and carry on.
+pub struct ChildGuard {
+    #[cfg_attr(not(unix), allow(dead_code))]
+    slot: Option<usize>,
+}
+
+/// Defers termination signals while the terminal is in a modified state
+pub struct InterruptGuard {
+    _private: (),
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{ChildGuard, InterruptGuard};
+    use anyhow::{Context, Result};
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGQUIT, SIGTERM, SIGWINCH};
+    use signal_hook::low_level;
+    use signal_hook_registry::register_sigaction;
+    use std::os::fd::RawFd;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+    use std::sync::OnceLock;
+
+    /// Signals that would normally terminate crabcrust
+    const TERMINATING: [i32; 4] = [SIGINT, SIGTERM, SIGHUP, SIGQUIT];
+
+    /// Maximum number of children that can be forwarded to at once
+    const MAX_CHILDREN: usize = 16;
+
+    /// Child pids (0 = free slot) and their PTY masters (-1 = no PTY)
+    static CHILD_PIDS: [AtomicI32; MAX_CHILDREN] = [const { AtomicI32::new(0) }; MAX_CHILDREN];
+    static PTY_MASTERS: [AtomicI32; MAX_CHILDREN] = [const { AtomicI32::new(-1) }; MAX_CHILDREN];
+    /// Last signal forwarded to the child in each slot (0 = none)
+    static FORWARDED: [AtomicI32; MAX_CHILDREN] = [const { AtomicI32::new(0) }; MAX_CHILDREN];
+    static DEFERRING: AtomicUsize = AtomicUsize::new(0);
+    static PENDING: AtomicI32 = AtomicI32::new(0);
+    static INSTALLED: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+
+    /// Install the process-wide handlers (once)
+    fn install() -> Result<()> {
+        INSTALLED
+            .get_or_init(|| {
+                for sig in TERMINATING {
+                    // SAFETY: the handler only touches atomics and async-signal-safe calls
+                    unsafe { register_sigaction(sig, move |info| on_terminate(sig, info)) }
+                        .map_err(|e| e.to_string())?;
+                }
+                // SAFETY: as above; SIGWINCH is ignored by default so nothing is lost
+                unsafe { low_level::register(SIGWINCH, on_resize) }.map_err(|e| e.to_string())?;
+                Ok(())
+            })
+            .clone()
+            .map_err(anyhow::Error::msg)
+            .context("Failed to install signal handlers")
+    }
+
+    fn on_terminate(sig: i32, info: &libc::siginfo_t) {
+        let mut forwarding = false;
+        for (slot, (pid, master)) in CHILD_PIDS.iter().zip(&PTY_MASTERS).enumerate() {
+            let pid = pid.load(Ordering::SeqCst);
+            if pid <= 0 {
+                continue;
+            }
+            forwarding = true;
+            FORWARDED[slot].store(sig, Ordering::SeqCst);
+
+            // Terminal-generated signals already reach children in our process group
+            let own_session = master.load(Ordering::SeqCst) >= 0;
+            if own_session || !from_kernel(info) {
+                // SAFETY: kill is async-signal-safe
+                unsafe {
+                    libc::kill(pid, sig);
+                }
+            }
+        }
+
+        // A forwarded signal is up to the child; it's reported if the child dies
+        if forwarding {
+            return;
+        }
+        if DEFERRING.load(Ordering::SeqCst) > 0 {
+            PENDING.store(sig, Ordering::SeqCst);
+        } else {
+            let _ = low_level::emulate_default_handler(sig);
+        }
+    }
+
+    /// Check if the kernel raised the signal, e.g. for Ctrl-C on the terminal
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn from_kernel(info: &libc::siginfo_t) -> bool {
+        info.si_code == libc::SI_KERNEL
+    }
+
+    /// Other platforms don't tell, so treat every signal as sent by a process
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn from_kernel(_info: &libc::siginfo_t) -> bool {
+        false
+    }
+
+    fn on_resize() {
+        for master in &PTY_MASTERS {
+            let master = master.load(Ordering::SeqCst);
+            if master >= 0 {
+                // Resizing the PTY makes the kernel signal the child's process group
+                copy_window_size(libc::STDOUT_FILENO, master);
+            }
+        }
+    }
+
+    /// Copy the window size of one terminal onto another (no-op if not a TTY)
+    pub(crate) fn copy_window_size(from: RawFd, to: RawFd) {
+        // SAFETY: winsize is plain data and the ioctls only read/write it
+        unsafe {
+            let mut size: libc::winsize = std::mem::zeroed();
+            if libc::ioctl(from, libc::TIOCGWINSZ, &mut size) == 0 {
+                libc::ioctl(to, libc::TIOCSWINSZ, &size);
+            }
+        }
+    }
+
+    /// Forward termination signals to `pid` while the guard is alive
+    ///
+    /// Pass the PTY master for children running in their own session: they
+    /// don't see keyboard signals directly, and resizes must go to the PTY.
+    pub fn forward_to_child(pid: u32, pty_master: Option<RawFd>) -> Result<ChildGuard> {
+        install()?;
+
+        for (slot, child) in CHILD_PIDS.iter().enumerate() {
+            if child
+                .compare_exchange(0, pid as i32, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                PTY_MASTERS[slot].store(pty_master.unwrap_or(-1), Ordering::SeqCst);
+                return Ok(ChildGuard { slot: Some(slot) });
+            }
+        }
+
+        // Every slot is taken; the child still runs, just without forwarding
+        Ok(ChildGuard { slot: None })
+    }
+
+    impl ChildGuard {
+        /// Report a forwarded signal through [`pending`] if the child died from it
+        pub fn child_exited(&self, status: &ExitStatus) {
+            let Some(slot) = self.slot else {
+                return;
+            };
+            let forwarded = FORWARDED[slot].swap(0, Ordering::SeqCst);
+            if forwarded != 0 && status.signal() == Some(forwarded) {
+                PENDING.store(forwarded, Ordering::SeqCst);
+            }
+        }
+    }
+
+    impl Drop for ChildGuard {
+        fn drop(&mut self) {
+            if let Some(slot) = self.slot {
+                // A signal the child survived is forgotten
+                FORWARDED[slot].store(0, Ordering::SeqCst);
+                CHILD_PIDS[slot].store(0, Ordering::SeqCst);
+                PTY_MASTERS[slot].store(-1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Defer termination signals until the guard is dropped
+    ///
+    /// Deferred signals are reported by [`pending`] so render loops can stop
+    /// and unwind, letting cleanup guards restore the terminal.
+    pub fn defer_interrupts() -> Result<InterruptGuard> {
+        install()?;
+        DEFERRING.fetch_add(1, Ordering::SeqCst);
+        Ok(InterruptGuard { _private: () })
+    }
+
+    impl Drop for InterruptGuard {
+        fn drop(&mut self) {
+            DEFERRING.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn child_pid_in_slot(slot: usize) -> i32 {
+        CHILD_PIDS[slot].load(Ordering::SeqCst)
+    }
+
+    /// Take the last termination signal received while deferred, or forwarded
+    /// to a child that died from it
+    ///
+    /// The signal is cleared, so each one is reported once.
+    pub fn pending() -> Option<i32> {
+        match PENDING.swap(0, Ordering::SeqCst) {
+            0 => None,
+            sig => Some(sig),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod fallback {
+    use super::{ChildGuard, InterruptGuard};
+    use anyhow::Result;
+    use std::process::ExitStatus;
+
+    impl ChildGuard {
+        /// Nothing is forwarded on this platform, so there is nothing to report
+        pub fn child_exited(&self, _status: &ExitStatus) {}
+    }
+
+    /// Signal forwarding is not supported on this platform
+    pub fn forward_to_child(_pid: u32, _pty_master: Option<i32>) -> Result<ChildGuard> {
+        Ok(ChildGuard { slot: None })
+    }
+
+    /// Signal deferral is not supported on this platform
+    pub fn defer_interrupts() -> Result<InterruptGuard> {
+        Ok(InterruptGuard { _private: () })
+    }
+
+    /// Always `None` on this platform
+    pub fn pending() -> Option<i32> {
+        None
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interrupted_exit_code() {
+        let interrupted = Interrupted { signal: 2 };
+        assert_eq!(interrupted.exit_code(), 130);
+        assert_eq!(interrupted.to_string(), "interrupted by signal 2");
+    }
+
+    #[test]
+    fn test_child_guard_releases_slot() {
+        let guard = forward_to_child(u32::MAX >> 1, None).expect("Failed to install handlers");
+        let slot = guard.slot.expect("No free forwarding slot");
+        assert_eq!(unix::child_pid_in_slot(slot), (u32::MAX >> 1) as i32);
+
+        drop(guard);
+        assert_eq!(unix::child_pid_in_slot(slot), 0);
+    }
+}
@@ -0,0 +1,522 @@
+// Cargo-specific wrapper with build- and test-aware animations
+//
+// For build-like subcommands cargo is asked for JSON messages on stdout
+// (`--message-format=json-diagnostic-rendered-ansi`). Each compiled crate
+// advances a progress bar, and the messages decide which celebration to show
+// at the end. Before the output is printed, the JSON lines are replaced by
+// the diagnostics rustc rendered into them, on stderr where cargo puts them.
+
+use super::pager::strip_ansi;
+use super::{CliWrapper, FAILURE_ANIMATIONS};
+use crate::animation::{
+    Animation, AnimationParams, AnimationPlayer, AnimationRegistry, ProgressAnimation,
+    TintedAnimation,
+};
+use crate::braille::Color;
+use crate::config::Config;
+use crate::executor::{CommandExecutor, CommandResult, ExecutionMode, OutputLine, OutputStream};
+use anyhow::Result;
+use serde::Deserialize;
+use std::io::IsTerminal;
+
+/// Subcommands the shell integration hands to crabcrust
+///
+/// `run` isn't among them: the program it starts may want the terminal.
+pub const SUBCOMMANDS: &[&str] = &[
+    "build", "b", "check", "c", "test", "t", "clippy", "publish",
+];
+
+/// Subcommands that accept `--message-format`
+const JSON_SUBCOMMANDS: &[&str] = &[
+    "build", "b", "check", "c", "test", "t", "run", "r", "clippy",
+];
+
+/// Global options that take a value as the next argument
+pub(crate) const VALUE_OPTIONS: &[&str] = &[
+    "-C", "--color", "--config", "--explain", "--manifest-path", "-Z",
+];
+
+/// Message format that carries rustc's colored diagnostics in each message
+const MESSAGE_FORMAT: &str = "--message-format=json-diagnostic-rendered-ansi";
+
+/// Color for the celebration when the build produced warnings
+const WARNING_COLOR: Color = Color::new(255, 200, 0);
+
+/// A message cargo prints with `--message-format=json`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum CargoMessage {
+    CompilerArtifact {
+        fresh: bool,
+    },
+    CompilerMessage {
+        message: Diagnostic,
+    },
+    BuildFinished {
+        success: bool,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// The parts of a rustc diagnostic we look at
+#[derive(Debug, Deserialize)]
+struct Diagnostic {
+    level: String,
+    /// The diagnostic as rustc would have printed it
+    rendered: Option<String>,
+}
+
+/// Parse a line of cargo output as a JSON message
+fn parse_message(line: &str) -> Option<CargoMessage> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    serde_json::from_str(line).ok()
+}
+
+/// Facts collected from a cargo run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CargoReport {
+    /// Crates compiled in this run
+    pub compiled: usize,
+    /// Crates that were already up to date
+    pub fresh: usize,
+    pub warnings: usize,
+    pub errors: usize,
+    pub tests_passed: usize,
+    pub tests_failed: usize,
+    pub tests_ignored: usize,
+    /// Number of `test result:` lines seen (one per test binary)
+    pub test_suites: usize,
+    /// Whether cargo reported the build itself as successful
+    pub build_succeeded: Option<bool>,
+}
+
+impl CargoReport {
+    /// Build a report from a finished command's output
+    pub fn from_output(stdout: &str) -> Self {
+        let mut report = Self::default();
+        for line in stdout.lines() {
+            report.observe(line);
+        }
+        report
+    }
+
+    /// Update the report with one line of stdout
+    ///
+    /// Returns true if the line was a JSON message rather than program output.
+    pub fn observe(&mut self, line: &str) -> bool {
+        match parse_message(line) {
+            Some(message) => {
+                self.record(message);
+                true
+            }
+            None => {
+                self.record_test_result(line);
+                false
+            }
+        }
+    }
+
+    fn record(&mut self, message: CargoMessage) {
+        match message {
+            CargoMessage::CompilerArtifact { fresh: true } => self.fresh += 1,
+            CargoMessage::CompilerArtifact { fresh: false } => self.compiled += 1,
+            CargoMessage::CompilerMessage { message } => match message.level.as_str() {
+                "warning" => self.warnings += 1,
+                "error" => self.errors += 1,
+                _ => {}
+            },
+            CargoMessage::BuildFinished { success } => self.build_succeeded = Some(success),
+            _ => {}
+        }
+    }
+
+    /// Parse libtest's `test result: ok. 3 passed; 0 failed; 1 ignored; ...`
+    fn record_test_result(&mut self, line: &str) {
+        let Some(rest) = line.trim().strip_prefix("test result: ") else {
+            return;
+        };
+        self.test_suites += 1;
+
+        let counts = rest.split_once(". ").map_or(rest, |(_, counts)| counts);
+        for part in counts.split(';') {
+            let mut words = part.split_whitespace();
+            let (Some(count), Some(kind)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let Ok(count) = count.parse::<usize>() else {
+                continue;
+            };
+            match kind {
+                "passed" => self.tests_passed += count,
+                "failed" => self.tests_failed += count,
+                "ignored" => self.tests_ignored += count,
+                _ => {}
+            }
+        }
+    }
+
+    /// One-line summary worth printing after the output, if any
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+
+        if self.test_suites > 0 {
+            let total = self.tests_passed + self.tests_failed;
+            if self.tests_failed > 0 {
+                parts.push(format!("✗ {} of {} tests failed", self.tests_failed, total));
+            } else {
+                parts.push(format!("✓ {} tests passed", self.tests_passed));
+            }
+            if self.tests_ignored > 0 {
+                parts.push(format!("{} ignored", self.tests_ignored));
+            }
+        }
+        if self.errors > 0 {
+            parts.push(format!(
+                "✗ {} {}",
+                self.errors,
+                plural(self.errors, "error")
+            ));
+        }
+        if self.warnings > 0 {
+            parts.push(format!(
+                "⚠ {} {}",
+                self.warnings,
+                plural(self.warnings, "warning")
+            ));
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(format!("🦀 {}", parts.join(", ")))
+        }
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.to_string()
+    } else {
+        format!("{}s", word)
+    }
+}
+
+/// Find the position of the cargo subcommand, skipping `+toolchain` and
+/// global options with their values
+fn subcommand_index(args: &[&str]) -> Option<usize> {
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i];
+        if VALUE_OPTIONS.contains(&arg) {
+            i += 2;
+        } else if arg.starts_with('+') || arg.starts_with('-') {
+            i += 1;
+        } else {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Add the JSON message format after the subcommand, unless already given
+///
+/// Returns `None` when cargo's output should be left alone.
+fn with_message_format(args: &[&str]) -> Option<Vec<String>> {
+    let index = subcommand_index(args)?;
+    if !JSON_SUBCOMMANDS.contains(&args[index]) {
+        return None;
+    }
+
+    // Only look at cargo's own arguments, not those after `--`
+    let own_args = args.iter().take_while(|arg| **arg != "--");
+    if own_args
+        .into_iter()
+        .any(|arg| arg.starts_with("--message-format"))
+    {
+        return None;
+    }
+
+    let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    args.insert(index + 1, MESSAGE_FORMAT.to_string());
+    Some(args)
+}
+
+/// The rendered diagnostic carried by a JSON message, if any
+fn rendered_diagnostic(message: CargoMessage, color: bool) -> Option<String> {
+    let CargoMessage::CompilerMessage { message } = message else {
+        return None;
+    };
+    let rendered = message.rendered?;
+    Some(if color { rendered } else { strip_ansi(&rendered) })
+}
+
+/// Replace cargo's JSON messages with the diagnostics rendered into them
+///
+/// The diagnostics move to stderr, where cargo itself would have printed them.
+fn render_messages(result: &mut CommandResult, color: bool) {
+    let mut stdout = String::new();
+    for line in result.stdout.split_inclusive('\n') {
+        match parse_message(line.trim_end()) {
+            Some(message) => {
+                if let Some(rendered) = rendered_diagnostic(message, color) {
+                    result.stderr.push_str(&rendered);
+                }
+            }
+            None => stdout.push_str(line),
+        }
+    }
+    result.stdout = stdout;
+
+    let mut lines = Vec::with_capacity(result.lines.len());
+    for line in result.lines.drain(..) {
+        let message = match line.stream {
+            OutputStream::Stdout => parse_message(&line.text),
+            OutputStream::Stderr => None,
+        };
+        let Some(message) = message else {
+            lines.push(line);
+            continue;
+        };
+        if let Some(rendered) = rendered_diagnostic(message, color) {
+            lines.extend(rendered.lines().map(|text| OutputLine {
+                stream: OutputStream::Stderr,
+                text: text.to_string(),
+                terminator: Some('\n'),
+            }));
+        }
+    }
+    result.lines = lines;
+}
+
+/// Cargo command wrapper with build- and test-aware animations
+pub struct CargoWrapper {
+    wrapper: CliWrapper,
+}
+
+impl CargoWrapper {
+    /// Create a new Cargo wrapper
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            wrapper: CliWrapper::new()?,
+        })
+    }
+
+    /// Set how cargo is executed relative to the animation
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.wrapper = self.wrapper.with_mode(mode);
+        self
+    }
+
+    /// Use animations and settings from a configuration file
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.wrapper = self.wrapper.with_config(config);
+        self
+    }
+
+    /// Execute a cargo command with appropriate animation
+    pub fn run(&mut self, args: &[&str]) -> Result<CommandResult> {
+        if !self.wrapper.animations_enabled() {
+            return self.wrapper.passthrough(&CommandExecutor::new("cargo", args));
+        }
+
+        let subcommand = subcommand_index(args).map(|i| args[i]).unwrap_or("");
+        // `cargo +nightly build` is configured as `build`
+        self.wrapper.set_subcommand(Some(subcommand).filter(|s| !s.is_empty()));
+
+        // JSON would be shown raw on a PTY, so leave cargo's output alone there
+        let json_args = match self.wrapper.mode() {
+            ExecutionMode::Pty => None,
+            _ => with_message_format(args),
+        };
+        let executor = match &json_args {
+            Some(json_args) => {
+                let arg_refs: Vec<&str> = json_args.iter().map(|s| s.as_str()).collect();
+                CommandExecutor::new("cargo", &arg_refs)
+            }
+            None => CommandExecutor::new("cargo", args),
+        };
+
+        // Each compiled crate advances the progress bar
+        let progress = ProgressAnimation::new();
+        let handle = progress.handle();
+        let running: Option<Box<dyn Animation>> = match json_args {
+            Some(_) => Some(Box::new(progress)),
+            None => None,
+        };
+        let mut live = CargoReport::default();
+        let (mut player, mut result) = self.wrapper.execute_with(&executor, running, |line| {
+            if line.stream != OutputStream::Stdout || !live.observe(&line.text) {
+                return true;
+            }
+            handle.set_completed(live.compiled + live.fresh);
+            if live.build_succeeded.is_some() {
+                handle.set_total(handle.completed().max(1));
+            }
+            false
+        })?;
+
+        let report = CargoReport::from_output(&result.stdout);
+        render_messages(&mut result, std::io::stderr().is_terminal());
+
+        self.celebrate(&mut player, &executor, &result, &report, subcommand)?;
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+        if let Some(summary) = report.summary() {
+            eprintln!("{}", summary);
+        }
+
+        Ok(result)
+    }
+
+    /// Pick the animation for how the run went
+    fn celebrate(
+        &self,
+        player: &mut AnimationPlayer,
+        executor: &CommandExecutor,
+        result: &CommandResult,
+        report: &CargoReport,
+        subcommand: &str,
+    ) -> Result<()> {
+        if self.wrapper.play_configured(player, executor, result)? {
+            return Ok(());
+        }
+
+        let defaults = AnimationParams::default();
+        if !result.success {
+            // Failing tests end the game; compile errors blow up
+            let names = if report.tests_failed > 0 {
+                &["game-over"][..]
+            } else if report.errors > 0 {
+                &["explosion", "red-x"][..]
+            } else {
+                FAILURE_ANIMATIONS
+            };
+            return self.wrapper.play_one_of(player, names);
+        }
+
+        if report.warnings > 0 {
+            // Passed, but not cleanly: a subdued, yellow celebration
+            let confetti = AnimationRegistry::create_global("confetti", &defaults)?;
+            return player.play(TintedAnimation::new(confetti, WARNING_COLOR));
+        }
+
+        match subcommand {
+            "test" | "t" => self.wrapper.play_named(player, "trophy", &defaults),
+            "run" | "r" => Ok(()),
+            "publish" => self.wrapper.play_named(player, "rocket", &defaults),
+            _ => self.wrapper.play_named(player, "save", &defaults),
+        }
+    }
+}
+
+impl Default for CargoWrapper {
+    fn default() -> Self {
+        Self::new().expect("Failed to create Cargo wrapper")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Captured from cargo 1.95 building a crate with an unused variable, then
+    // rebuilding it, and building one that calls `compile_error!("boom")`
+    const WARNING_BUILD: &str = include_str!("testdata/cargo_warning.jsonl");
+    const FRESH_BUILD: &str = include_str!("testdata/cargo_fresh.jsonl");
+    const ERROR_BUILD: &str = include_str!("testdata/cargo_error.jsonl");
+
+    #[test]
+    fn test_report_from_messages() {
+        let output = [
+            WARNING_BUILD,
+            "running 3 tests",
+            "test result: FAILED. 2 passed; 1 failed; 4 ignored; 0 measured; 0 filtered out",
+            "test result: ok. 5 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out",
+        ]
+        .join("\n");
+        let report = CargoReport::from_output(&output);
+
+        assert_eq!(report.compiled, 1);
+        assert_eq!(report.fresh, 0);
+        assert_eq!(report.warnings, 1);
+        assert_eq!(report.build_succeeded, Some(true));
+        assert_eq!(report.test_suites, 2);
+        assert_eq!(report.tests_passed, 7);
+        assert_eq!(report.tests_failed, 1);
+        assert_eq!(report.tests_ignored, 4);
+        assert_eq!(
+            report.summary().as_deref(),
+            Some("🦀 ✗ 1 of 8 tests failed, 4 ignored, ⚠ 1 warning")
+        );
+
+        // Cached warnings are replayed on a fresh build
+        let report = CargoReport::from_output(FRESH_BUILD);
+        assert_eq!((report.compiled, report.fresh, report.warnings), (0, 1, 1));
+
+        let report = CargoReport::from_output(ERROR_BUILD);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.build_succeeded, Some(false));
+        assert_eq!(report.summary().as_deref(), Some("🦀 ✗ 1 error"));
+    }
+
+    #[test]
+    fn test_message_format_insertion() {
+        let args = with_message_format(&["+nightly", "test", "--", "--nocapture"]).unwrap();
+        assert_eq!(
+            args,
+            vec!["+nightly", "test", MESSAGE_FORMAT, "--", "--nocapture"]
+        );
+
+        assert!(with_message_format(&["build", "--message-format=short"]).is_none());
+        assert!(with_message_format(&["publish"]).is_none());
+        assert!(with_message_format(&[]).is_none());
+
+        // A program argument that looks like the flag is not cargo's
+        let args = with_message_format(&["run", "--", "--message-format"]).unwrap();
+        assert_eq!(args[1], MESSAGE_FORMAT);
+    }
+
+    #[test]
+    fn test_subcommand_index() {
+        assert_eq!(subcommand_index(&["+nightly", "build"]), Some(1));
+        assert_eq!(subcommand_index(&["--config", "foo.toml", "build"]), Some(2));
+        assert_eq!(subcommand_index(&["-Z", "unstable", "build"]), Some(2));
+        assert_eq!(subcommand_index(&["--color", "always", "test"]), Some(2));
+        assert_eq!(subcommand_index(&["--color=always", "-Zunstable", "check"]), Some(2));
+        assert_eq!(subcommand_index(&["--config", "foo.toml"]), None);
+
+        let args = with_message_format(&["--color", "always", "test"]).unwrap();
+        assert_eq!(args, vec!["--color", "always", "test", MESSAGE_FORMAT]);
+    }
+
+    #[test]
+    fn test_render_messages() {
+        let mut result = CommandResult {
+            stdout: format!("{}hello\n", WARNING_BUILD),
+            stderr: "   Compiling scratch v0.1.0\n".to_string(),
+            ..Default::default()
+        };
+        render_messages(&mut result, false);
+        assert_eq!(result.stdout, "hello\n");
+        assert!(result.stderr.starts_with("   Compiling scratch v0.1.0\nwarning: unused variable"));
+        assert!(!result.stderr.contains('\x1b'));
+
+        let lines = ERROR_BUILD.lines().map(|text| OutputLine {
+            stream: OutputStream::Stdout,
+            text: text.to_string(),
+            terminator: Some('\n'),
+        });
+        let mut result = CommandResult {
+            lines: lines.collect(),
+            ..Default::default()
+        };
+        render_messages(&mut result, true);
+        assert!(result.lines.iter().all(|line| line.stream == OutputStream::Stderr));
+        assert!(result.lines[0].text.contains("\x1b[1m: boom"));
+    }
+}
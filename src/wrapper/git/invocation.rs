@@ -0,0 +1,263 @@
+// Locating the real subcommand in a git command line
+//
+// git accepts global options before the subcommand (`git -C repo push`) and
+// lets users define aliases (`git co`). Both are resolved here so the wrapper
+// can pick the right flow.
+
+use crate::executor::CommandExecutor;
+
+/// Global options that take a value as the next argument
+pub(crate) const VALUE_OPTIONS: &[&str] = &[
+    "-C",
+    "-c",
+    "--git-dir",
+    "--work-tree",
+    "--namespace",
+    "--super-prefix",
+    "--attr-source",
+];
+
+/// Commands git never looks up as aliases (aliases can't shadow builtins)
+///
+/// The builtins of recent git versions; anything else costs a config lookup.
+const BUILTINS: &[&str] = &[
+    "add", "am", "annotate", "apply", "archive", "bisect", "blame", "branch", "bugreport", "bundle",
+    "cat-file", "check-attr", "check-ignore", "check-mailmap", "check-ref-format", "checkout",
+    "checkout-index", "cherry", "cherry-pick", "clean", "clone", "column", "commit", "commit-graph",
+    "commit-tree", "config", "count-objects", "credential", "credential-cache", "credential-store",
+    "describe", "diagnose", "diff", "diff-files", "diff-index", "diff-tree", "difftool",
+    "fast-export", "fast-import", "fetch", "fetch-pack", "fmt-merge-msg", "for-each-ref",
+    "for-each-repo", "format-patch", "fsck", "fsck-objects", "gc", "get-tar-commit-id", "grep",
+    "hash-object", "help", "hook", "index-pack", "init", "init-db", "interpret-trailers", "log",
+    "ls-files", "ls-remote", "ls-tree", "mailinfo", "mailsplit", "maintenance", "merge",
+    "merge-base", "merge-file", "merge-index", "merge-ours", "merge-recursive",
+    "merge-recursive-ours", "merge-recursive-theirs", "merge-subtree", "merge-tree", "mktag",
+    "mktree", "multi-pack-index", "mv", "name-rev", "notes", "pack-objects", "pack-redundant",
+    "pack-refs", "patch-id", "pickaxe", "prune", "prune-packed", "pull", "push", "range-diff",
+    "read-tree", "rebase", "receive-pack", "reflog", "remote", "remote-ext", "remote-fd", "repack",
+    "replace", "rerere", "reset", "restore", "rev-list", "rev-parse", "revert", "rm", "send-pack",
+    "shortlog", "show", "show-branch", "show-index", "show-ref", "sparse-checkout", "stage",
+    "stash", "status", "stripspace", "submodule", "switch", "symbolic-ref", "tag", "unpack-file",
+    "unpack-objects", "update-index", "update-ref", "update-server-info", "upload-archive",
+    "upload-pack", "var", "verify-commit", "verify-pack", "verify-tag", "version", "whatchanged",
+    "worktree", "write-tree",
+];
+
+/// Aliases expanding to more aliases are followed at most this deep
+const MAX_ALIAS_DEPTH: usize = 10;
+
+/// A git command line split around its subcommand
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitInvocation {
+    /// Global options before the subcommand, e.g. `-C repo`
+    pub global: Vec<String>,
+    /// Position of the subcommand (as typed) in the original arguments
+    pub index: Option<usize>,
+    /// The subcommand after alias resolution, e.g. `checkout` for `co`
+    ///
+    /// `None` without a subcommand (`git --version`) or for shell aliases.
+    pub subcommand: Option<String>,
+    /// Arguments of the resolved subcommand, alias arguments first
+    pub args: Vec<String>,
+}
+
+impl GitInvocation {
+    /// Parse a command line, resolving aliases from git's configuration
+    pub fn resolve(args: &[&str]) -> Self {
+        let global = Self::parse(args).global;
+        Self::resolve_with(args, |name| Self::lookup_alias(&global, name))
+    }
+
+    /// Parse a command line, resolving aliases with `lookup`
+    pub fn resolve_with<F>(args: &[&str], mut lookup: F) -> Self
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut invocation = Self::parse(args);
+        let mut seen = Vec::new();
+
+        while let Some(name) = invocation.subcommand.take() {
+            if BUILTINS.contains(&name.as_str()) || seen.len() >= MAX_ALIAS_DEPTH || seen.contains(&name) {
+                invocation.subcommand = Some(name);
+                break;
+            }
+            let Some(value) = lookup(&name) else {
+                invocation.subcommand = Some(name);
+                break;
+            };
+            // Shell aliases run arbitrary commands; there's no telling what
+            if value.starts_with('!') {
+                break;
+            }
+            let mut words = split_cmdline(&value);
+            if words.is_empty() {
+                invocation.subcommand = Some(name);
+                break;
+            }
+            invocation.subcommand = Some(words.remove(0));
+            words.append(&mut invocation.args);
+            invocation.args = words;
+            seen.push(name);
+        }
+
+        invocation
+    }
+
+    /// Split a command line around its subcommand, without resolving aliases
+    pub fn parse(args: &[&str]) -> Self {
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i];
+            if VALUE_OPTIONS.contains(&arg) {
+                i += 2;
+            } else if arg.starts_with('-') {
+                i += 1;
+            } else {
+                return Self {
+                    global: Self::owned(&args[..i]),
+                    index: Some(i),
+                    subcommand: Some(arg.to_string()),
+                    args: Self::owned(&args[i + 1..]),
+                };
+            }
+        }
+
+        Self {
+            global: Self::owned(&args[..i.min(args.len())]),
+            ..Default::default()
+        }
+    }
+
+    /// Get the resolved subcommand, or "" if there is none
+    pub fn subcommand(&self) -> &str {
+        self.subcommand.as_deref().unwrap_or("")
+    }
+
+    /// Get the subcommand's arguments as string slices
+    pub fn args(&self) -> Vec<&str> {
+        self.args.iter().map(String::as_str).collect()
+    }
+
+    /// Ask git for the value of `alias.<name>`, honoring the global options
+    fn lookup_alias(global: &[String], name: &str) -> Option<String> {
+        let key = format!("alias.{}", name);
+        let mut args: Vec<&str> = global.iter().map(String::as_str).collect();
+        args.extend(["config", "--get", &key]);
+
+        let result = CommandExecutor::new("git", &args).run().ok()?;
+        result.success.then(|| result.stdout.trim().to_string())
+    }
+
+    fn owned(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+}
+
+/// Split an alias value into words the way git does
+///
+/// Words are separated by whitespace; single quotes keep everything
+/// literally, double quotes allow backslash escapes.
+fn split_cmdline(value: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('"') | None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_global_options() {
+        let invocation = GitInvocation::parse(&["-C", "repo", "-c", "user.name=x", "push", "-f"]);
+        assert_eq!(invocation.global, vec!["-C", "repo", "-c", "user.name=x"]);
+        assert_eq!(invocation.index, Some(4));
+        assert_eq!(invocation.subcommand(), "push");
+        assert_eq!(invocation.args(), vec!["-f"]);
+
+        let invocation = GitInvocation::parse(&["--no-pager", "--git-dir=.git", "log"]);
+        assert_eq!(invocation.subcommand(), "log");
+
+        let invocation = GitInvocation::parse(&["--version"]);
+        assert_eq!(invocation.subcommand, None);
+        assert_eq!(invocation.index, None);
+
+        // A value option at the end has no subcommand after it
+        assert_eq!(GitInvocation::parse(&["-C"]).subcommand, None);
+    }
+
+    #[test]
+    fn test_resolve_aliases() {
+        let lookup = |name: &str| match name {
+            "co" => Some("checkout".to_string()),
+            "up" => Some("pull --rebase".to_string()),
+            "sync" => Some("up --autostash".to_string()),
+            "loop" => Some("loop".to_string()),
+            "visual" => Some("!gitk".to_string()),
+            _ => None,
+        };
+
+        let invocation = GitInvocation::resolve_with(&["-C", "repo", "co", "main"], lookup);
+        assert_eq!(invocation.subcommand(), "checkout");
+        assert_eq!(invocation.args(), vec!["main"]);
+        assert_eq!(invocation.index, Some(2));
+
+        let invocation = GitInvocation::resolve_with(&["sync", "origin"], lookup);
+        assert_eq!(invocation.subcommand(), "pull");
+        assert_eq!(invocation.args(), vec!["--rebase", "--autostash", "origin"]);
+
+        assert_eq!(GitInvocation::resolve_with(&["loop"], lookup).subcommand(), "loop");
+        assert_eq!(GitInvocation::resolve_with(&["visual"], lookup).subcommand, None);
+        assert_eq!(GitInvocation::resolve_with(&["frobnicate"], lookup).subcommand(), "frobnicate");
+
+        // Builtins are never looked up
+        for builtin in ["switch", "restore", "grep", "worktree"] {
+            let invocation =
+                GitInvocation::resolve_with(&[builtin], |name| panic!("looked up {}", name));
+            assert_eq!(invocation.subcommand(), builtin);
+        }
+    }
+
+    #[test]
+    fn test_split_cmdline() {
+        assert_eq!(split_cmdline("log --graph  --oneline"), vec!["log", "--graph", "--oneline"]);
+        assert_eq!(
+            split_cmdline(r#"commit -m "quick fix" --author='A B'"#),
+            vec!["commit", "-m", "quick fix", "--author=A B"]
+        );
+        assert_eq!(split_cmdline(r#"a\ b """#), vec!["a b", ""]);
+    }
+}
@@ -0,0 +1,897 @@
+// Git-specific wrapper with custom animations
+
+mod invocation;
+mod outcome;
+
+pub use invocation::GitInvocation;
+pub(crate) use invocation::VALUE_OPTIONS;
+pub use outcome::{ChangeSize, GitOutcome, MergeKind, Rejection};
+
+use super::pager::{strip_ansi, Pager};
+use super::CliWrapper;
+use crate::animation::{AnimationParams, AnimationPlayer, ProgressAnimation};
+use crate::config::Config;
+use crate::executor::{CommandExecutor, CommandResult, ExecutionMode};
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+#[cfg(any(feature = "gif", feature = "video"))]
+use crate::dmd_library;
+
+/// Subcommands with their own animations, as dispatched in [`GitWrapper::run`]
+pub const SUBCOMMANDS: &[&str] = &[
+    "commit",
+    "push",
+    "pull",
+    "merge",
+    "status",
+    "diff",
+    "log",
+    "show",
+    "rebase",
+    "cherry-pick",
+    "stash",
+    "tag",
+    "bisect",
+    "clone",
+    "fetch",
+];
+
+/// Celebrations picked from after a successful push
+const PUSH_ANIMATIONS: &[&str] = &["rocket", "fireworks", "trophy", "confetti"];
+
+/// Celebrations picked from after a successful pull
+const PULL_ANIMATIONS: &[&str] = &["download", "rabbit"];
+
+/// Commit celebrations, growing with the size of the change
+const SMALL_COMMIT_ANIMATIONS: &[&str] = &["save"];
+const MEDIUM_COMMIT_ANIMATIONS: &[&str] = &["baby", "confetti"];
+const LARGE_COMMIT_ANIMATIONS: &[&str] = &["fireworks", "trophy"];
+
+/// Shown when a push is rejected
+const PUSH_FAILURE_ANIMATIONS: &[&str] = &["explosion", "game-over"];
+
+/// Shown when a pull or merge stops on conflicts
+const CONFLICT_ANIMATIONS: &[&str] = &["tilt"];
+
+/// Shown for other failures
+const ERROR_ANIMATIONS: &[&str] = &["red-x"];
+
+/// Shown when a rebase replayed its commits cleanly
+const REBASE_ANIMATIONS: &[&str] = &["rabbit", "rocket"];
+
+/// Shown when a cherry-pick applied cleanly
+const CHERRY_PICK_ANIMATIONS: &[&str] = &["confetti", "save"];
+
+/// Release-style celebrations for annotated or version tags
+const RELEASE_ANIMATIONS: &[&str] = &["fireworks", "rocket", "trophy"];
+
+/// How long the bisect progress bar is shown after each step
+const BISECT_PROGRESS_DURATION: Duration = Duration::from_millis(1500);
+
+/// Screen height assumed when the terminal can't tell
+const DEFAULT_SCREEN_ROWS: usize = 24;
+
+/// `git tag` options that take a value as the next argument
+const TAG_VALUE_OPTIONS: &[&str] = &[
+    "-m",
+    "--message",
+    "-F",
+    "--file",
+    "-u",
+    "--local-user",
+    "--cleanup",
+    "--sort",
+    "--format",
+    "--contains",
+    "--no-contains",
+    "--merged",
+    "--no-merged",
+    "--points-at",
+];
+
+/// Git command wrapper with themed animations
+pub struct GitWrapper {
+    wrapper: CliWrapper,
+}
+
+impl GitWrapper {
+    /// Create a new Git wrapper
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            wrapper: CliWrapper::new()?,
+        })
+    }
+
+    /// Set how git is executed relative to the animation
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.wrapper = self.wrapper.with_mode(mode);
+        self
+    }
+
+    /// Use animations and settings from a configuration file
+    ///
+    /// Configured animations take precedence over the built-in ones.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.wrapper = self.wrapper.with_config(config);
+        self
+    }
+
+    /// Execute a git command with appropriate animation
+    pub fn run(&mut self, args: &[&str]) -> Result<CommandResult> {
+        let executor = CommandExecutor::new("git", args);
+        if !self.wrapper.animations_enabled() {
+            return self.wrapper.passthrough(&executor);
+        }
+
+        // Determine animation based on git subcommand, looking past global
+        // options and through aliases
+        let invocation = GitInvocation::resolve(args);
+        let subcommand = invocation.subcommand();
+        let rest = &invocation.args()[..];
+        self.wrapper.set_subcommand(invocation.subcommand.as_deref());
+
+        match subcommand {
+            "commit" => self.run_commit(executor, rest),
+            "push" => self.run_push(executor),
+            "pull" => self.run_pull(executor),
+            "merge" => self.run_merge(executor),
+            "status" | "diff" | "log" | "show" => self.run_status(executor, &invocation),
+            "rebase" => self.run_rebase(executor, rest),
+            "cherry-pick" => self.run_cherry_pick(executor, rest),
+            "stash" => self.run_stash(executor, rest),
+            "tag" => self.run_tag(executor, rest),
+            "bisect" => self.run_bisect(executor, &invocation.global),
+            "clone" | "fetch" => self.run_transfer(args, &invocation),
+            _ => self.wrapper.run_with_default_animations(executor),
+        }
+    }
+
+    /// Run git commit with celebration animation
+    fn run_commit(&mut self, executor: CommandExecutor, args: &[&str]) -> Result<CommandResult> {
+        // Show loading animation until the command exits, or hand over the
+        // terminal when the message is written in an editor
+        let (mut player, result) = self.execute(&executor, opens_commit_editor(args))?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // Show success animation - the bigger the commit, the bigger the party
+        if outcome.is_noop() {
+            // Nothing to commit: nothing to celebrate, and not really an error
+        } else if self.wrapper.play_configured(&mut player, &executor, &result)? {
+            // Configured animation replaces the built-in one
+        } else if result.success {
+            let names = match outcome.size() {
+                ChangeSize::Small => SMALL_COMMIT_ANIMATIONS,
+                ChangeSize::Medium => MEDIUM_COMMIT_ANIMATIONS,
+                ChangeSize::Large => LARGE_COMMIT_ANIMATIONS,
+            };
+            self.play_one_of(&mut player, names)?;
+        } else {
+            self.play_one_of(&mut player, ERROR_ANIMATIONS)?;
+        }
+
+        // Print output after animation completes
+        drop(player); // Clean up renderer
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git push with epic celebration animation
+    fn run_push(&mut self, executor: CommandExecutor) -> Result<CommandResult> {
+        // Show loading animation until the command exits
+        let (mut player, result) = self.wrapper.execute(&executor)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // Show success animation - DMD VICTORY or fallback to procedural
+        if outcome.is_noop() {
+            // Everything up-to-date: nothing went anywhere
+        } else if self.wrapper.play_configured(&mut player, &executor, &result)? {
+            // Configured animation replaces the built-in one
+        } else if result.success {
+            #[cfg(any(feature = "gif", feature = "video"))]
+            {
+                // Try to load DMD animation, fallback to procedural if it fails
+                match dmd_library::load_dmd_for_git_command("push", false) {
+                    Some(Ok(dmd_anim)) => {
+                        player.play(dmd_anim)?;
+                    }
+                    _ => {
+                        // Fallback to procedural animations
+                        self.play_one_of(&mut player, PUSH_ANIMATIONS)?;
+                    }
+                }
+            }
+            #[cfg(not(any(feature = "gif", feature = "video")))]
+            {
+                self.play_one_of(&mut player, PUSH_ANIMATIONS)?;
+            }
+        } else if outcome.was_rejected() {
+            self.play_one_of(&mut player, PUSH_FAILURE_ANIMATIONS)?;
+        } else {
+            self.play_one_of(&mut player, ERROR_ANIMATIONS)?;
+        }
+
+        // Print output after animation completes
+        drop(player); // Clean up renderer
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git pull with download/rabbit animation
+    fn run_pull(&mut self, executor: CommandExecutor) -> Result<CommandResult> {
+        // Show loading animation until the command exits
+        let (mut player, result) = self.wrapper.execute(&executor)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // Show DMD or fallback animation on success
+        if outcome.is_noop() {
+            // Already up to date: nothing was pulled
+        } else if self.wrapper.play_configured(&mut player, &executor, &result)? {
+            // Configured animation replaces the built-in one
+        } else if outcome.has_conflicts() {
+            self.play_one_of(&mut player, CONFLICT_ANIMATIONS)?;
+        } else if result.success && outcome.merge == Some(MergeKind::MergeCommit) {
+            // Diverged branches were joined rather than fast-forwarded
+            self.play_one_of(&mut player, &["merge"])?;
+        } else if result.success {
+            #[cfg(any(feature = "gif", feature = "video"))]
+            {
+                match dmd_library::load_dmd_for_git_command("pull", false) {
+                    Some(Ok(dmd_anim)) => {
+                        player.play(dmd_anim)?;
+                    }
+                    _ => {
+                        self.play_one_of(&mut player, PULL_ANIMATIONS)?;
+                    }
+                }
+            }
+            #[cfg(not(any(feature = "gif", feature = "video")))]
+            {
+                self.play_one_of(&mut player, PULL_ANIMATIONS)?;
+            }
+        } else {
+            self.play_one_of(&mut player, ERROR_ANIMATIONS)?;
+        }
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git merge with merge animation
+    fn run_merge(&mut self, executor: CommandExecutor) -> Result<CommandResult> {
+        // Show loading animation until the command exits
+        let (mut player, result) = self.wrapper.execute(&executor)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // Show DMD or fallback animation on success
+        if outcome.is_noop() {
+            // Already up to date: nothing was merged
+        } else if self.wrapper.play_configured(&mut player, &executor, &result)? {
+            // Configured animation replaces the built-in one
+        } else if outcome.has_conflicts() {
+            self.play_one_of(&mut player, CONFLICT_ANIMATIONS)?;
+        } else if result.success {
+            #[cfg(any(feature = "gif", feature = "video"))]
+            {
+                match dmd_library::load_dmd_for_git_command("merge", false) {
+                    Some(Ok(dmd_anim)) => {
+                        player.play(dmd_anim)?;
+                    }
+                    _ => {
+                        self.play_one_of(&mut player, &["merge"])?;
+                    }
+                }
+            }
+            #[cfg(not(any(feature = "gif", feature = "video")))]
+            {
+                self.play_one_of(&mut player, &["merge"])?;
+            }
+        } else {
+            self.play_one_of(&mut player, ERROR_ANIMATIONS)?;
+        }
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git status/diff/log/show with Matrix Rain decode animation
+    ///
+    /// Only the first screenful is decoded. The rest then goes to the pager
+    /// git would use, or straight to stdout, instead of being kept in memory.
+    fn run_status(
+        &mut self,
+        executor: CommandExecutor,
+        invocation: &GitInvocation,
+    ) -> Result<CommandResult> {
+        if self.wrapper.mode() != ExecutionMode::Pty {
+            // git pages everything but status unless configured otherwise
+            let subcommand = invocation.subcommand();
+            let global: Vec<&str> = invocation.global.iter().map(String::as_str).collect();
+            let pager = Pager::for_git(&global, subcommand, subcommand != "status");
+            let executor = match pager {
+                // Keep git's colors although it's writing to a pipe
+                Some(_) => executor.with_env("GIT_PAGER_IN_USE", "true"),
+                None => executor,
+            };
+            return self.run_paged(executor, pager);
+        }
+
+        // Execute command on the PTY, showing its output live
+        let (mut player, result) = self.wrapper.execute(&executor)?;
+
+        if result.echoed {
+            // Output was already shown live on the terminal
+            drop(player);
+            return Ok(result);
+        }
+
+        if self.wrapper.play_configured(&mut player, &executor, &result)? {
+            // Configured animation already played
+        } else if result.success {
+            // Play Matrix rain that decodes the start of the output
+            let output = result.combined_output();
+            self.play_matrix(&mut player, &first_lines(&output, screen_rows()))?;
+        } else {
+            // On error, shake the X
+            self.play_one_of(&mut player, ERROR_ANIMATIONS)?;
+        }
+
+        // The animation area is cleared, so print the output for real
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Decode the first screenful, then stream everything into the pager, or
+    /// to stdout without one
+    fn run_paged(
+        &mut self,
+        executor: CommandExecutor,
+        pager: Option<Pager>,
+    ) -> Result<CommandResult> {
+        let mut paged = executor.spawn_paged(screen_rows())?;
+
+        let mut player = self.wrapper.player(&executor)?;
+        let running = self.wrapper.running_animation(&executor, None)?;
+        player.play_while(running, || !paged.is_ready())?;
+
+        // Short output is printed like without a pager, as `less -F` would
+        if paged.is_complete()? {
+            let result = paged.wait()?;
+            if self.wrapper.play_configured(&mut player, &executor, &result)? {
+                // Configured animation already played
+            } else if result.success {
+                self.play_matrix(&mut player, &strip_ansi(&result.stdout))?;
+            } else {
+                self.play_one_of(&mut player, ERROR_ANIMATIONS)?;
+            }
+            drop(player);
+            result.print_output();
+            return Ok(result);
+        }
+
+        let head = strip_ansi(&String::from_utf8_lossy(paged.head()?));
+        self.play_matrix(&mut player, &head)?;
+        drop(player);
+
+        let result = match pager.map(|pager| pager.spawn()) {
+            Some(Ok(mut child)) => {
+                let stdin = child.stdin.take().context("Failed to open pager input")?;
+                let result = paged.pipe_to(stdin);
+                // Leave the terminal to the pager until the user quits it
+                let _ = child.wait();
+                result?
+            }
+            Some(Err(err)) => {
+                eprintln!("crabcrust: {:#}", err);
+                paged.pipe_to(std::io::stdout().lock())?
+            }
+            None => paged.pipe_to(std::io::stdout().lock())?,
+        };
+        if !result.stderr.is_empty() {
+            eprint!("{}", result.stderr);
+        }
+
+        Ok(result)
+    }
+
+    /// Play Matrix rain decoding `text`
+    fn play_matrix(&self, player: &mut AnimationPlayer, text: &str) -> Result<()> {
+        let params = AnimationParams {
+            text: Some(text.to_string()),
+            ..Default::default()
+        };
+        self.wrapper.play_named(player, "matrix", &params)
+    }
+
+    /// Run git rebase, handing over the terminal when an editor may open
+    fn run_rebase(&mut self, executor: CommandExecutor, args: &[&str]) -> Result<CommandResult> {
+        let interactive = args
+            .iter()
+            .any(|arg| matches!(*arg, "-i" | "--interactive" | "--continue" | "--edit-todo"));
+        let (mut player, result) = self.execute(&executor, interactive)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // Backing out of a rebase is nothing to celebrate
+        let success = if has_any(args, &["--abort", "--quit"]) {
+            &[]
+        } else {
+            REBASE_ANIMATIONS
+        };
+        self.play_outcome(&mut player, &executor, &result, &outcome, success)?;
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git cherry-pick with conflict-aware animations
+    fn run_cherry_pick(
+        &mut self,
+        executor: CommandExecutor,
+        args: &[&str],
+    ) -> Result<CommandResult> {
+        let interactive = has_any(args, &["--continue", "-e", "--edit"]);
+        let (mut player, result) = self.execute(&executor, interactive)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        let success = if has_any(args, &["--abort", "--quit"]) {
+            &[]
+        } else {
+            CHERRY_PICK_ANIMATIONS
+        };
+        self.play_outcome(&mut player, &executor, &result, &outcome, success)?;
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git stash: saving and restoring get their own animations
+    fn run_stash(&mut self, executor: CommandExecutor, args: &[&str]) -> Result<CommandResult> {
+        let (mut player, result) = self.execute(&executor, false)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // A bare `git stash` or one starting with options is a push
+        let action = match args.first() {
+            Some(arg) if !arg.starts_with('-') => *arg,
+            _ => "push",
+        };
+        let success: &[&str] = match action {
+            "push" | "save" => &["save"],
+            "pop" | "apply" => &["download"],
+            _ => &[],
+        };
+        self.play_outcome(&mut player, &executor, &result, &outcome, success)?;
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git tag with a release-style celebration for real releases
+    fn run_tag(&mut self, executor: CommandExecutor, args: &[&str]) -> Result<CommandResult> {
+        let tag = created_tag(args);
+        let annotated = is_annotated_tag(args);
+
+        // `git tag -a v1.0` without a message opens an editor
+        let interactive =
+            tag.is_some() && (has_any(args, &["-e", "--edit"]) || (annotated && !has_message(args)));
+        let (mut player, result) = self.execute(&executor, interactive)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // Listing, deleting or verifying tags plays nothing on success
+        let success = match tag {
+            Some(name) if annotated || is_version_tag(name) => RELEASE_ANIMATIONS,
+            Some(_) => &["save"],
+            None => &[],
+        };
+        self.play_outcome(&mut player, &executor, &result, &outcome, success)?;
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git bisect, showing how far the search has narrowed
+    fn run_bisect(&mut self, executor: CommandExecutor, global: &[String]) -> Result<CommandResult> {
+        let (mut player, result) = self.execute(&executor, false)?;
+        let outcome = GitOutcome::from_result(&result);
+
+        if self.wrapper.play_configured(&mut player, &executor, &result)? {
+            // Configured animation replaces the built-in one
+        } else if !result.success {
+            self.play_one_of(&mut player, ERROR_ANIMATIONS)?;
+        } else if outcome.bisect_found {
+            // Found the culprit!
+            self.play_one_of(&mut player, &["trophy"])?;
+        } else if let Some(left) = outcome.bisect_steps_left {
+            // One tick per step taken, out of the steps taken plus those left
+            let done = bisect_steps_done(global);
+            let progress = ProgressAnimation::new();
+            let handle = progress.handle();
+            handle.set_total(done + left + 1);
+            handle.set_completed(done);
+            player.play_for(progress, BISECT_PROGRESS_DURATION)?;
+        }
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Run git clone or fetch with a transfer progress bar
+    fn run_transfer(
+        &mut self,
+        args: &[&str],
+        invocation: &GitInvocation,
+    ) -> Result<CommandResult> {
+        // git only reports progress to a terminal unless asked; the lines
+        // can only drive the bar when output is streamed
+        let args: Vec<String> = match (self.wrapper.mode(), invocation.index) {
+            (ExecutionMode::Streaming, Some(index)) => {
+                with_progress(args, index, &invocation.args())
+            }
+            _ => args.iter().map(|arg| arg.to_string()).collect(),
+        };
+        let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let executor = CommandExecutor::new("git", &arg_refs);
+
+        let progress = ProgressAnimation::new();
+        let handle = progress.handle();
+        let (mut player, result) =
+            self.wrapper
+                .execute_with(&executor, Some(Box::new(progress)), |line| {
+                    if let Some(percent) = receiving_percent(&line.text) {
+                        handle.set_total(100);
+                        handle.set_completed(percent);
+                    }
+                    true
+                })?;
+        let outcome = GitOutcome::from_result(&result);
+
+        // A fetch without ref updates brought in nothing new
+        let success = if invocation.subcommand() == "fetch" && outcome.refs_updated == 0 {
+            &[]
+        } else {
+            PULL_ANIMATIONS
+        };
+        self.play_outcome(&mut player, &executor, &result, &outcome, success)?;
+
+        // Print output after animation completes
+        drop(player);
+        result.print_output();
+
+        Ok(result)
+    }
+
+    /// Execute with the running animation, or on a PTY if an editor may open
+    fn execute(
+        &self,
+        executor: &CommandExecutor,
+        interactive: bool,
+    ) -> Result<(AnimationPlayer, CommandResult)> {
+        if interactive {
+            self.wrapper.execute_interactive(executor)
+        } else {
+            self.wrapper.execute(executor)
+        }
+    }
+
+    /// Play the animation for a finished command
+    ///
+    /// No-ops play nothing and a configured animation takes precedence;
+    /// otherwise conflicts and failures get their animations, and success
+    /// one of `success` (nothing if it's empty).
+    fn play_outcome(
+        &self,
+        player: &mut AnimationPlayer,
+        executor: &CommandExecutor,
+        result: &CommandResult,
+        outcome: &GitOutcome,
+        success: &[&str],
+    ) -> Result<()> {
+        if outcome.is_noop() || self.wrapper.play_configured(player, executor, result)? {
+            Ok(())
+        } else if outcome.has_conflicts() {
+            self.play_one_of(player, CONFLICT_ANIMATIONS)
+        } else if !result.success {
+            self.play_one_of(player, ERROR_ANIMATIONS)
+        } else if success.is_empty() {
+            Ok(())
+        } else {
+            self.play_one_of(player, success)
+        }
+    }
+
+    /// Play one of several registered animations, picked at random
+    fn play_one_of(&self, player: &mut AnimationPlayer, names: &[&str]) -> Result<()> {
+        self.wrapper.play_one_of(player, names)
+    }
+
+    /// Execute git command directly (for convenience)
+    pub fn commit(&mut self, message: &str) -> Result<CommandResult> {
+        self.run(&["commit", "-m", message])
+    }
+
+    pub fn push(&mut self) -> Result<CommandResult> {
+        self.run(&["push"])
+    }
+
+    pub fn pull(&mut self) -> Result<CommandResult> {
+        self.run(&["pull"])
+    }
+
+    pub fn status(&mut self) -> Result<CommandResult> {
+        self.run(&["status"])
+    }
+}
+
+/// Terminal height in rows
+fn screen_rows() -> usize {
+    match crossterm::terminal::size() {
+        Ok((_, rows)) if rows > 0 => rows as usize,
+        _ => DEFAULT_SCREEN_ROWS,
+    }
+}
+
+/// Get at most the first `count` lines of `text`
+fn first_lines(text: &str, count: usize) -> String {
+    text.lines().take(count).collect::<Vec<_>>().join("\n")
+}
+
+/// Check if any of `flags` was passed
+fn has_any(args: &[&str], flags: &[&str]) -> bool {
+    args.iter().any(|arg| flags.contains(arg))
+}
+
+/// Check if `git commit` opens an editor for the message
+///
+/// It does unless a message is given with `-m`, `-F` or `-C` (or `--fixup`),
+/// or `--no-edit` is passed; `-e` and `-c` open it regardless.
+fn opens_commit_editor(args: &[&str]) -> bool {
+    let (mut message, mut edit) = (false, false);
+    let mut args = args.iter().take_while(|arg| **arg != "--");
+    while let Some(arg) = args.next() {
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            let takes_value = match name {
+                "edit" => {
+                    edit = true;
+                    false
+                }
+                "no-edit" | "dry-run" => return false,
+                "message" | "file" | "reuse-message" => {
+                    message = true;
+                    true
+                }
+                "reedit-message" => {
+                    edit = true;
+                    true
+                }
+                "fixup" => {
+                    // `--fixup=amend:<commit>` and `reword:` edit the message
+                    let target = value.or_else(|| args.clone().next().copied()).unwrap_or("");
+                    message |= !target.starts_with("amend:") && !target.starts_with("reword:");
+                    true
+                }
+                "author" | "date" | "template" | "cleanup" | "squash" | "trailer" => true,
+                _ => false,
+            };
+            if takes_value && value.is_none() {
+                args.next();
+            }
+        } else if let Some(flags) = arg.strip_prefix('-') {
+            // Short options may be bundled, e.g. `-am <msg>`; an option
+            // taking a value ends the bundle
+            for (i, flag) in flags.char_indices() {
+                match flag {
+                    'e' => edit = true,
+                    'm' | 'F' | 'C' | 'c' | 't' => {
+                        message |= matches!(flag, 'm' | 'F' | 'C');
+                        edit |= flag == 'c';
+                        if i + 1 == flags.len() {
+                            args.next();
+                        }
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    edit || !message
+}
+
+/// Name of the tag `git tag` creates, or `None` when listing, deleting or verifying
+fn created_tag<'a>(args: &[&'a str]) -> Option<&'a str> {
+    let listing = args.iter().any(|arg| {
+        matches!(*arg, "-l" | "--list" | "-d" | "--delete" | "-v" | "--verify")
+            || (arg.starts_with("-n") && arg[2..].bytes().all(|b| b.is_ascii_digit()))
+    });
+    if listing {
+        return None;
+    }
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if TAG_VALUE_OPTIONS.contains(arg) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+/// Check if `git tag` creates an annotated (or signed) tag
+fn is_annotated_tag(args: &[&str]) -> bool {
+    args.iter().any(|arg| {
+        matches!(*arg, "-a" | "--annotate" | "-s" | "--sign")
+            || ["-u", "--local-user"].iter().any(|opt| arg.starts_with(opt))
+    }) || has_message(args)
+}
+
+/// Check if a tag message was given on the command line, which implies `-a`
+fn has_message(args: &[&str]) -> bool {
+    args.iter().any(|arg| {
+        ["-m", "--message", "-F", "--file"]
+            .iter()
+            .any(|opt| arg.starts_with(opt))
+    })
+}
+
+/// Check if a tag name looks like a semantic version, e.g. `v1.2.3-rc.1`
+fn is_version_tag(name: &str) -> bool {
+    let version = name.strip_prefix(['v', 'V']).unwrap_or(name);
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Count the good/bad/skip steps recorded in the bisect log
+///
+/// `global` are git's global options, e.g. `-C repo`, to find the right log.
+fn bisect_steps_done(global: &[String]) -> usize {
+    let mut args: Vec<&str> = global.iter().map(String::as_str).collect();
+    args.extend(["rev-parse", "--git-path", "BISECT_LOG"]);
+    let Ok(result) = CommandExecutor::new("git", &args).run() else {
+        return 0;
+    };
+    let Ok(log) = std::fs::read_to_string(result.stdout.trim()) else {
+        return 0;
+    };
+    log.lines()
+        .filter(|line| line.starts_with("git bisect ") && !line.starts_with("git bisect start"))
+        .count()
+}
+
+/// Add `--progress` after the subcommand at `index` unless the subcommand's
+/// arguments `sub_args` already say how to report progress
+fn with_progress(args: &[&str], index: usize, sub_args: &[&str]) -> Vec<String> {
+    let mut with: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    if !has_any(sub_args, &["--progress", "--no-progress", "-q", "--quiet"]) {
+        with.insert(index + 1, "--progress".to_string());
+    }
+    with
+}
+
+/// Parse the percentage from `Receiving objects:  45% (450/1000), 1.2 MiB`
+fn receiving_percent(line: &str) -> Option<usize> {
+    let (_, rest) = line.split_once("Receiving objects:")?;
+    let (percent, _) = rest.trim_start().split_once('%')?;
+    percent.parse().ok()
+}
+
+impl Default for GitWrapper {
+    fn default() -> Self {
+        Self::new().expect("Failed to create Git wrapper")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_wrapper_creation() {
+        let wrapper = GitWrapper::new();
+        assert!(wrapper.is_ok());
+    }
+
+    #[test]
+    fn test_opens_commit_editor() {
+        assert!(opens_commit_editor(&[]));
+        assert!(opens_commit_editor(&["-a", "--amend"]));
+        assert!(opens_commit_editor(&["-m", "wip", "-e"]));
+        assert!(opens_commit_editor(&["-c", "HEAD"]));
+        assert!(opens_commit_editor(&["--fixup=amend:HEAD"]));
+        assert!(!opens_commit_editor(&["-am", "Fix the build"]));
+        assert!(!opens_commit_editor(&["--message=Fix", "--", "-e"]));
+        assert!(!opens_commit_editor(&["-m", "-e"]));
+        assert!(!opens_commit_editor(&["-F", "msg.txt"]));
+        assert!(!opens_commit_editor(&["-C", "HEAD"]));
+        assert!(!opens_commit_editor(&["--amend", "--no-edit"]));
+        assert!(!opens_commit_editor(&["--fixup", "HEAD~2"]));
+    }
+
+    #[test]
+    fn test_created_tag() {
+        assert_eq!(created_tag(&["v1.2.0"]), Some("v1.2.0"));
+        assert_eq!(created_tag(&["-m", "Release v2", "v2.0.0"]), Some("v2.0.0"));
+        assert_eq!(created_tag(&["-a", "nightly", "HEAD~1"]), Some("nightly"));
+        assert_eq!(created_tag(&[]), None);
+        assert_eq!(created_tag(&["-l", "v1.*"]), None);
+        assert_eq!(created_tag(&["-d", "v1.2.0"]), None);
+        assert_eq!(created_tag(&["-n3"]), None);
+        assert_eq!(created_tag(&["--contains", "HEAD"]), None);
+
+        assert!(is_annotated_tag(&["-a", "v1"]));
+        assert!(is_annotated_tag(&["-mRelease", "v1"]));
+        assert!(!is_annotated_tag(&["v1"]));
+        assert!(!has_message(&["-a", "v1"]));
+    }
+
+    #[test]
+    fn test_version_tag() {
+        assert!(is_version_tag("v1.2.3"));
+        assert!(is_version_tag("0.10.0"));
+        assert!(is_version_tag("v2.0.0-rc.1+build.5"));
+        assert!(!is_version_tag("v1.2"));
+        assert!(!is_version_tag("nightly"));
+        assert!(!is_version_tag("v1..3"));
+    }
+
+    #[test]
+    fn test_transfer_progress() {
+        assert_eq!(
+            with_progress(&["fetch", "origin"], 0, &["origin"]),
+            vec!["fetch", "--progress", "origin"]
+        );
+        assert_eq!(
+            with_progress(&["-C", "repo", "fetch"], 2, &[]),
+            vec!["-C", "repo", "fetch", "--progress"]
+        );
+        assert_eq!(
+            with_progress(&["clone", "-q", "url"], 0, &["-q", "url"]),
+            vec!["clone", "-q", "url"]
+        );
+        assert_eq!(
+            receiving_percent("Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s"),
+            Some(45)
+        );
+        assert_eq!(receiving_percent("Resolving deltas: 100% (20/20)"), None);
+    }
+
+    #[test]
+    fn test_first_lines() {
+        assert_eq!(first_lines("a\nb\nc\n", 2), "a\nb");
+        assert_eq!(first_lines("a\n", 5), "a");
+        assert_eq!(first_lines("", 5), "");
+    }
+}
@@ -0,0 +1,277 @@
+// Facts parsed from git's output, used to pick fitting animations
+
+use crate::executor::CommandResult;
+
+/// Lines changed up to which a commit counts as small
+const SMALL_CHANGE_LINES: usize = 20;
+
+/// Lines changed up to which a commit counts as medium
+const MEDIUM_CHANGE_LINES: usize = 200;
+
+/// How a pull or merge integrated the other branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeKind {
+    /// The branch pointer just moved forward
+    FastForward,
+    /// A merge commit was created
+    MergeCommit,
+}
+
+/// Rough size of a change, for scaling celebrations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeSize {
+    Small,
+    Medium,
+    Large,
+}
+
+/// A ref the remote refused to update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    /// Remote ref that was rejected, e.g. `main`
+    pub reference: String,
+    /// Reason git gave, e.g. `non-fast-forward`
+    pub reason: String,
+}
+
+/// What a git command did, as far as its output tells
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitOutcome {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// "Already up to date." or "Everything up-to-date"
+    pub up_to_date: bool,
+    /// "nothing to commit" and friends, including an empty stash
+    pub nothing_to_commit: bool,
+    pub merge: Option<MergeKind>,
+    pub rejected: Vec<Rejection>,
+    /// Paths reported in `CONFLICT` lines
+    pub conflicts: Vec<String>,
+    /// Ref update lines such as `main -> origin/main` from fetch or push
+    pub refs_updated: usize,
+    /// Steps bisect expects to need after the current one
+    pub bisect_steps_left: Option<usize>,
+    /// Bisect has found the first bad commit
+    pub bisect_found: bool,
+}
+
+impl GitOutcome {
+    /// Parse the combined output of a finished git command
+    pub fn from_result(result: &CommandResult) -> Self {
+        Self::parse(&result.combined_output())
+    }
+
+    /// Parse git output
+    pub fn parse(output: &str) -> Self {
+        let mut outcome = Self::default();
+        for line in output.lines() {
+            outcome.parse_line(line.trim());
+        }
+        outcome
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        if line.starts_with(|c: char| c.is_ascii_digit()) && line.contains(" changed") {
+            self.parse_stat(line);
+        } else if line.starts_with("Already up to date")
+            || line.starts_with("Already up-to-date")
+            || line == "Everything up-to-date"
+            || (line.starts_with("Current branch ") && line.ends_with(" is up to date."))
+        {
+            self.up_to_date = true;
+        } else if line.starts_with("nothing to commit")
+            || line.starts_with("nothing added to commit")
+            || line.starts_with("no changes added to commit")
+            || line == "No local changes to save"
+        {
+            self.nothing_to_commit = true;
+        } else if line == "Fast-forward" {
+            self.merge = Some(MergeKind::FastForward);
+        } else if line.starts_with("Merge made by") {
+            self.merge = Some(MergeKind::MergeCommit);
+        } else if let Some(rest) = line
+            .strip_prefix("! [rejected]")
+            .or_else(|| line.strip_prefix("! [remote rejected]"))
+        {
+            self.rejected.push(Self::parse_rejection(rest.trim()));
+        } else if let Some(rest) = line.strip_prefix("CONFLICT (") {
+            if let Some(path) = Self::parse_conflict(rest) {
+                self.conflicts.push(path);
+            }
+        } else if line.starts_with("Bisecting: ") {
+            self.bisect_steps_left = Self::parse_bisect_steps(line);
+        } else if line.ends_with(" is the first bad commit") {
+            self.bisect_found = true;
+        } else if line.contains(" -> ") {
+            self.refs_updated += 1;
+        }
+    }
+
+    /// Parse `Bisecting: 12 revisions left to test after this (roughly 4 steps)`
+    fn parse_bisect_steps(line: &str) -> Option<usize> {
+        let (_, rest) = line.split_once("(roughly ")?;
+        rest.split_whitespace().next()?.parse().ok()
+    }
+
+    /// Parse ` 3 files changed, 10 insertions(+), 2 deletions(-)`
+    fn parse_stat(&mut self, line: &str) {
+        for part in line.split(',') {
+            let mut words = part.split_whitespace();
+            let (Some(count), Some(kind)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let Ok(count) = count.parse::<usize>() else {
+                continue;
+            };
+            if kind.starts_with("file") {
+                self.files_changed += count;
+            } else if kind.starts_with("insertion") {
+                self.insertions += count;
+            } else if kind.starts_with("deletion") {
+                self.deletions += count;
+            }
+        }
+    }
+
+    /// Parse `main -> main (non-fast-forward)`
+    fn parse_rejection(rest: &str) -> Rejection {
+        let (refs, reason) = match rest.rsplit_once(" (") {
+            Some((refs, reason)) => (refs, reason.trim_end_matches(')')),
+            None => (rest, ""),
+        };
+        let reference = refs.rsplit(" -> ").next().unwrap_or(refs).trim();
+        Rejection {
+            reference: reference.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Get the path from `content): Merge conflict in src/lib.rs`
+    fn parse_conflict(rest: &str) -> Option<String> {
+        let (_, detail) = rest.split_once("): ")?;
+        let path = match detail.split_once("Merge conflict in ") {
+            Some((_, path)) => path,
+            // e.g. `file.txt deleted in HEAD and modified in topic.`
+            None => detail.split_whitespace().next()?,
+        };
+        Some(path.to_string())
+    }
+
+    /// Check if the command changed nothing
+    pub fn is_noop(&self) -> bool {
+        self.up_to_date || self.nothing_to_commit
+    }
+
+    /// Check if the remote refused any refs
+    pub fn was_rejected(&self) -> bool {
+        !self.rejected.is_empty()
+    }
+
+    /// Check if git stopped on merge conflicts
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// Total lines added and removed
+    pub fn lines_changed(&self) -> usize {
+        self.insertions + self.deletions
+    }
+
+    /// Size of the change from the diffstat
+    pub fn size(&self) -> ChangeSize {
+        match self.lines_changed() {
+            n if n <= SMALL_CHANGE_LINES => ChangeSize::Small,
+            n if n <= MEDIUM_CHANGE_LINES => ChangeSize::Medium,
+            _ => ChangeSize::Large,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commit() {
+        let outcome = GitOutcome::parse(
+            "[main 1a2b3c4] Add parser\n 3 files changed, 250 insertions(+), 12 deletions(-)\n create mode 100644 src/parser.rs\n",
+        );
+        assert_eq!(outcome.files_changed, 3);
+        assert_eq!(outcome.insertions, 250);
+        assert_eq!(outcome.deletions, 12);
+        assert_eq!(outcome.size(), ChangeSize::Large);
+        assert!(!outcome.is_noop());
+
+        let outcome = GitOutcome::parse(" 1 file changed, 1 insertion(+)");
+        assert_eq!(outcome.size(), ChangeSize::Small);
+
+        let outcome = GitOutcome::parse("On branch main\nnothing to commit, working tree clean\n");
+        assert!(outcome.nothing_to_commit);
+        assert!(outcome.is_noop());
+    }
+
+    #[test]
+    fn test_parse_pull() {
+        let outcome = GitOutcome::parse(
+            "Updating 1a2b3c4..5d6e7f8\nFast-forward\n README.md | 2 +-\n 1 file changed, 1 insertion(+), 1 deletion(-)\n",
+        );
+        assert_eq!(outcome.merge, Some(MergeKind::FastForward));
+        assert_eq!(outcome.lines_changed(), 2);
+
+        let outcome = GitOutcome::parse("Merge made by the 'ort' strategy.\n a.txt | 1 +\n");
+        assert_eq!(outcome.merge, Some(MergeKind::MergeCommit));
+
+        assert!(GitOutcome::parse("Already up to date.\n").up_to_date);
+        assert!(GitOutcome::parse("Everything up-to-date\n").is_noop());
+        assert!(GitOutcome::parse("Current branch main is up to date.\n").is_noop());
+        assert!(GitOutcome::parse("No local changes to save\n").is_noop());
+
+        let outcome = GitOutcome::parse(
+            "From github.com:me/repo\n   1a2b3c4..5d6e7f8  main       -> origin/main\n * [new tag]         v1.0.0     -> v1.0.0\n",
+        );
+        assert_eq!(outcome.refs_updated, 2);
+    }
+
+    #[test]
+    fn test_parse_bisect() {
+        let outcome = GitOutcome::parse(
+            "Bisecting: 12 revisions left to test after this (roughly 4 steps)\n[1a2b3c4] Refactor parser\n",
+        );
+        assert_eq!(outcome.bisect_steps_left, Some(4));
+        assert!(!outcome.bisect_found);
+
+        let outcome = GitOutcome::parse("1a2b3c4d is the first bad commit\ncommit 1a2b3c4d\n");
+        assert!(outcome.bisect_found);
+    }
+
+    #[test]
+    fn test_parse_rejected_push() {
+        let outcome = GitOutcome::parse(
+            "To github.com:me/repo.git\n ! [rejected]        main -> main (non-fast-forward)\n ! [remote rejected] dev -> dev (pre-receive hook declined)\nerror: failed to push some refs\n",
+        );
+        assert!(outcome.was_rejected());
+        assert_eq!(
+            outcome.rejected,
+            vec![
+                Rejection {
+                    reference: "main".to_string(),
+                    reason: "non-fast-forward".to_string(),
+                },
+                Rejection {
+                    reference: "dev".to_string(),
+                    reason: "pre-receive hook declined".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_conflicts() {
+        let outcome = GitOutcome::parse(
+            "Auto-merging src/lib.rs\nCONFLICT (content): Merge conflict in src/lib.rs\nCONFLICT (modify/delete): old.rs deleted in HEAD and modified in topic.\nAutomatic merge failed; fix conflicts and then commit the result.\n",
+        );
+        assert!(outcome.has_conflicts());
+        assert_eq!(outcome.conflicts, vec!["src/lib.rs", "old.rs"]);
+    }
+}
@@ -1,19 +1,303 @@
 // Wrapper module for integrating animations with CLI commands
 
+pub mod cargo;
 pub mod git;
+pub mod pager;
 
-use crate::animation::{AnimationPlayer, SaveAnimation, SpinnerAnimation};
-use crate::executor::{CommandExecutor, CommandResult};
+use crate::animation::{Animation, AnimationParams, AnimationPlayer, AnimationRegistry};
+use crate::braille::Color;
+use crate::config::{Config, Outcome, RenderModeSetting};
+use crate::executor::{CommandExecutor, CommandResult, ExecutionMode, OutputLine, OutputStream};
+use crate::rendering::{OutputPane, TerminalSupport};
 use anyhow::Result;
-use std::time::Duration;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Rows reserved beneath the animation for live output in streaming mode
+const OUTPUT_PANE_ROWS: u16 = 5;
+
+/// Pane color for lines written to stderr
+const STDERR_COLOR: Color = Color::new(255, 110, 110);
+
+/// How long configured animations without a natural end are played
+const OPEN_ENDED_DURATION: Duration = Duration::from_secs(1);
+
+/// Animations picked from when a command fails
+pub const FAILURE_ANIMATIONS: &[&str] = &["explosion", "red-x", "tilt", "game-over"];
 
 /// Wrapper for CLI commands with animations
-pub struct CliWrapper;
+pub struct CliWrapper {
+    mode: ExecutionMode,
+    config: Config,
+    /// Subcommand for configuration lookups, if not the first argument
+    subcommand: Option<String>,
+    /// Animations are shown; otherwise commands run as plain passthrough
+    animations: bool,
+}
 
 impl CliWrapper {
     /// Create a new CLI wrapper
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        Ok(Self {
+            mode: ExecutionMode::default(),
+            config: Config::default(),
+            subcommand: None,
+            animations: TerminalSupport::detect().animations_enabled(),
+        })
+    }
+
+    /// Use animations and settings from a configuration file
+    ///
+    /// The configured execution mode replaces the current one; call
+    /// [`with_mode`](Self::with_mode) afterwards to override it again.
+    pub fn with_config(mut self, config: Config) -> Self {
+        if let Some(mode) = config.exec_mode {
+            self.mode = mode;
+        }
+        self.config = config;
+        self
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Set the subcommand used to look up configured animations
+    ///
+    /// By default it's the command's first argument, which is wrong for tools
+    /// that take options before the subcommand (`git -C repo push`) or have
+    /// aliases. `None` restores the default.
+    pub fn set_subcommand(&mut self, subcommand: Option<&str>) {
+        self.subcommand = subcommand.map(str::to_string);
+    }
+
+    /// Set how commands are executed relative to the animation
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the execution mode
+    pub fn mode(&self) -> ExecutionMode {
+        self.mode
+    }
+
+    /// Turn animations on or off
+    ///
+    /// They're on by default only when the terminal supports them (see
+    /// [`TerminalSupport`]).
+    pub fn with_animations(mut self, enabled: bool) -> Self {
+        self.animations = enabled;
+        self
+    }
+
+    /// Check if animations are shown
+    pub fn animations_enabled(&self) -> bool {
+        self.animations
+    }
+
+    /// Run a command without animations, leaving its output untouched
+    pub fn passthrough(&self, executor: &CommandExecutor) -> Result<CommandResult> {
+        executor.run_inherited()
+    }
+
+    /// Execute a command while showing the loading spinner
+    ///
+    /// Returns an inline player ready for the follow-up success/failure
+    /// animation. In [`ExecutionMode::Concurrent`] the spinner keeps running
+    /// until the command exits; [`ExecutionMode::Streaming`] also shows the
+    /// latest output lines beneath it; in [`ExecutionMode::Blocking`] it plays
+    /// briefly first. [`ExecutionMode::Pty`] hands the terminal to the command
+    /// and only reserves the animation area once it exits.
+    pub fn execute(&self, executor: &CommandExecutor) -> Result<(AnimationPlayer, CommandResult)> {
+        self.execute_with(executor, None, |_| true)
+    }
+
+    /// Execute a command, showing `running` and watching its output
+    ///
+    /// A running animation from the configuration still takes precedence;
+    /// without either, the spinner is shown. In [`ExecutionMode::Streaming`]
+    /// every output line is passed to `observe` as it arrives, and lines it
+    /// returns false for are kept out of the output pane.
+    pub fn execute_with<F>(
+        &self,
+        executor: &CommandExecutor,
+        running: Option<Box<dyn Animation>>,
+        mut observe: F,
+    ) -> Result<(AnimationPlayer, CommandResult)>
+    where
+        F: FnMut(&OutputLine) -> bool,
+    {
+        if self.mode == ExecutionMode::Pty {
+            return self.execute_interactive(executor);
+        }
+
+        let mut player = self.player(executor)?;
+        let running_animation = self.running_animation(executor, running)?;
+
+        let (played, result) = match self.mode {
+            ExecutionMode::Blocking => {
+                player.play_for(running_animation, Duration::from_millis(500))?;
+                (Ok(()), executor.run())
+            }
+            ExecutionMode::Concurrent => {
+                let running = executor.spawn()?;
+                let played = player.play_while(running_animation, || !running.is_finished());
+                (played, running.wait())
+            }
+            ExecutionMode::Streaming => {
+                let running = executor.spawn_streaming()?;
+                let mut pane = OutputPane::new(OUTPUT_PANE_ROWS);
+                let played = player.play_with_output(running_animation, &mut pane, |pane| {
+                    for line in running.drain_output() {
+                        if !observe(&line) {
+                            continue;
+                        }
+                        let color = match line.stream {
+                            OutputStream::Stdout => None,
+                            OutputStream::Stderr => Some(STDERR_COLOR),
+                        };
+                        pane.push(&line.text, color, line.is_progress());
+                    }
+                    !running.is_finished()
+                });
+                (played, running.wait())
+            }
+            ExecutionMode::Pty => unreachable!("PTY mode returns early"),
+        };
+        let result = result?;
+
+        // Interrupted mid-run: the command got the signal too and has exited,
+        // so restore the terminal and show what it printed before bailing out
+        if let Err(err) = played {
+            drop(player);
+            result.print_output();
+            return Err(err);
+        }
+
+        Ok((player, result))
+    }
+
+    /// Execute a command that needs the terminal, e.g. to open an editor
+    ///
+    /// Runs it on a PTY whatever the execution mode, so no running animation
+    /// is shown; the returned player is ready for the follow-up animation.
+    pub fn execute_interactive(
+        &self,
+        executor: &CommandExecutor,
+    ) -> Result<(AnimationPlayer, CommandResult)> {
+        let result = executor.run_pty()?;
+        Ok((self.player(executor)?, result))
+    }
+
+    /// Play the configured success/failure animation for a finished command
+    ///
+    /// Returns false without playing anything if the configuration doesn't
+    /// set one, so callers can fall back to their built-in animations.
+    pub fn play_configured(
+        &self,
+        player: &mut AnimationPlayer,
+        executor: &CommandExecutor,
+        result: &CommandResult,
+    ) -> Result<bool> {
+        let outcome = if result.success {
+            Outcome::Success
+        } else {
+            Outcome::Failure
+        };
+        let (command, subcommand) = self.config_key(executor);
+        let Some(spec) = self.config.animation(command, subcommand, outcome) else {
+            return Ok(false);
+        };
+        // A spec that can't be built (e.g. a missing GIF) falls back to the defaults
+        let Ok(animation) = spec.build() else {
+            return Ok(false);
+        };
+
+        Self::play_to_end(player, animation, spec.duration)?;
+        Ok(true)
+    }
+
+    /// Play a registered animation
+    pub fn play_named(
+        &self,
+        player: &mut AnimationPlayer,
+        name: &str,
+        params: &AnimationParams,
+    ) -> Result<()> {
+        let animation = AnimationRegistry::create_global(name, params)?;
+        Self::play_to_end(player, animation, params.duration)
+    }
+
+    /// Play one of several registered animations, picked at random
+    pub fn play_one_of(&self, player: &mut AnimationPlayer, names: &[&str]) -> Result<()> {
+        let name = names[random_choice(names.len())];
+        self.play_named(player, name, &AnimationParams::default())
+    }
+
+    /// Play an animation to completion, limiting open-ended ones to `duration`
+    fn play_to_end(
+        player: &mut AnimationPlayer,
+        animation: Box<dyn Animation>,
+        duration: Option<Duration>,
+    ) -> Result<()> {
+        if animation.duration().is_some() {
+            player.play(animation)
+        } else {
+            player.play_for(animation, duration.unwrap_or(OPEN_ENDED_DURATION))
+        }
+    }
+
+    /// Create the player for a command in its configured render mode
+    fn player(&self, executor: &CommandExecutor) -> Result<AnimationPlayer> {
+        let (command, subcommand) = self.config_key(executor);
+        let player = match self.config.render_mode(command, subcommand) {
+            Some(RenderModeSetting::Fullscreen) => AnimationPlayer::new(),
+            _ => match self.config.inline_height {
+                Some(height) => AnimationPlayer::inline(height),
+                // Inline player with 1/3 terminal height
+                None => AnimationPlayer::inline_auto(),
+            },
+        }?;
+        Ok(self.config.apply_to(player))
+    }
+
+    /// Get the configured animation shown while a command runs
+    fn configured_running(&self, executor: &CommandExecutor) -> Option<Box<dyn Animation>> {
+        let (command, subcommand) = self.config_key(executor);
+        self.config
+            .animation(command, subcommand, Outcome::Running)
+            .and_then(|spec| spec.build().ok())
+    }
+
+    /// Pick the animation shown while a command runs
+    ///
+    /// The configured one wins over `running`; without either it's the spinner.
+    fn running_animation(
+        &self,
+        executor: &CommandExecutor,
+        running: Option<Box<dyn Animation>>,
+    ) -> Result<Box<dyn Animation>> {
+        match (self.configured_running(executor), running) {
+            (Some(configured), _) => Ok(configured),
+            (None, Some(running)) => Ok(running),
+            (None, None) => AnimationRegistry::create_global("spinner", &AnimationParams::default()),
+        }
+    }
+
+    /// Command and subcommand names used to look up configured animations
+    fn config_key<'a>(&'a self, executor: &'a CommandExecutor) -> (&'a str, Option<&'a str>) {
+        let program = executor.program();
+        let command = Path::new(program)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(program);
+        let subcommand = self
+            .subcommand
+            .as_deref()
+            .or_else(|| executor.args().first().map(String::as_str));
+        (command, subcommand)
     }
 
     /// Run a command with default animations based on success/failure
@@ -21,30 +305,40 @@ impl CliWrapper {
         &mut self,
         executor: CommandExecutor,
     ) -> Result<CommandResult> {
-        // Create inline player with 1/3 terminal height
-        let mut player = AnimationPlayer::inline_auto()?;
+        if !self.animations {
+            return self.passthrough(&executor);
+        }
 
         // Show loading animation while command runs
-        player.play_for(SpinnerAnimation::new(), Duration::from_millis(500))?;
-
-        // Execute command
-        let result = executor.run()?;
+        let (mut player, result) = self.execute(&executor)?;
 
         // Show success or error animation
-        if result.success {
-            player.play(SaveAnimation::default())?;
+        if self.play_configured(&mut player, &executor, &result)? {
+            // Configured animation already played
+        } else if result.success {
+            self.play_named(&mut player, "save", &AnimationParams::default())?;
         } else {
-            player.play_for(SpinnerAnimation::new(), Duration::from_millis(500))?;
+            self.play_one_of(&mut player, FAILURE_ANIMATIONS)?;
         }
 
         // Print output after animation
         drop(player);
-        println!("{}", result.combined_output());
+        result.print_output();
 
         Ok(result)
     }
 }
 
+/// Get a pseudo-random choice from 0 to max (exclusive)
+fn random_choice(max: usize) -> usize {
+    // Simple pseudo-random based on system time
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    (now as usize) % max
+}
+
 impl Default for CliWrapper {
     fn default() -> Self {
         Self::new().expect("Failed to create CLI wrapper")
@@ -60,4 +354,40 @@ mod tests {
         let wrapper = CliWrapper::new();
         assert!(wrapper.is_ok());
     }
+
+    #[test]
+    fn test_cli_wrapper_mode() {
+        let wrapper = CliWrapper::new().unwrap();
+        assert_eq!(wrapper.mode(), ExecutionMode::Streaming);
+
+        let wrapper = wrapper.with_mode(ExecutionMode::Blocking);
+        assert_eq!(wrapper.mode(), ExecutionMode::Blocking);
+    }
+
+    #[test]
+    fn test_config_sets_mode() {
+        let config = Config {
+            exec_mode: Some(ExecutionMode::Concurrent),
+            ..Default::default()
+        };
+        let wrapper = CliWrapper::new().unwrap().with_config(config);
+        assert_eq!(wrapper.mode(), ExecutionMode::Concurrent);
+
+        let wrapper = wrapper.with_mode(ExecutionMode::Blocking);
+        assert_eq!(wrapper.mode(), ExecutionMode::Blocking);
+    }
+
+    #[test]
+    fn test_config_key() {
+        let mut wrapper = CliWrapper::new().unwrap();
+        let executor = CommandExecutor::new("/usr/bin/git", &["push", "origin"]);
+        assert_eq!(wrapper.config_key(&executor), ("git", Some("push")));
+
+        let executor = CommandExecutor::new("make", &[]);
+        assert_eq!(wrapper.config_key(&executor), ("make", None));
+
+        let executor = CommandExecutor::new("git", &["-C", "repo", "co"]);
+        wrapper.set_subcommand(Some("checkout"));
+        assert_eq!(wrapper.config_key(&executor), ("git", Some("checkout")));
+    }
 }
@@ -0,0 +1,207 @@
+// The user's pager, for output longer than a screen
+
+use crate::executor::CommandExecutor;
+use anyhow::{Context, Result};
+use std::io::IsTerminal;
+use std::process::{Child, Command, Stdio};
+
+/// Used when no pager is configured or set in the environment
+const DEFAULT_PAGER: &str = "less -R";
+
+/// What `pager.<cmd>` says about paging a git command
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PagerSetting {
+    /// Never page
+    Off,
+    /// Page with the usual pager
+    On,
+    /// Page with this command line
+    Command(String),
+}
+
+impl PagerSetting {
+    /// Interpret a value like git: booleans turn paging on or off, anything
+    /// else is the pager to use
+    fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "true" | "yes" | "on" | "1" => Self::On,
+            "false" | "no" | "off" | "0" => Self::Off,
+            _ => Self::Command(value.trim().to_string()),
+        }
+    }
+}
+
+/// A pager command line, run through the shell like git does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pager {
+    command: String,
+}
+
+impl Pager {
+    /// Get the pager git would use for `subcommand`, if it pages at all
+    ///
+    /// `global` are git's global options, e.g. `-C repo` or `--no-pager`,
+    /// and `by_default` tells whether git pages the command unless
+    /// configured otherwise. `None` when stdout isn't a terminal, paging is
+    /// turned off with `--no-pager` or `pager.<cmd>`, or the pager is empty
+    /// or `cat`.
+    pub fn for_git(global: &[&str], subcommand: &str, by_default: bool) -> Option<Self> {
+        let has = |flags: &[&str]| global.iter().any(|arg| flags.contains(arg));
+        if !std::io::stdout().is_terminal() || has(&["-P", "--no-pager"]) {
+            return None;
+        }
+
+        let forced = has(&["-p", "--paginate"]);
+        let (setting, core_pager) = read_config(global, subcommand);
+        let configured = match setting {
+            Some(PagerSetting::Off) if !forced => return None,
+            None if !forced && !by_default => return None,
+            Some(PagerSetting::Command(command)) => Some(command),
+            _ => core_pager,
+        };
+        Self::choose(
+            std::env::var("GIT_PAGER").ok().as_deref(),
+            configured.as_deref(),
+            std::env::var("PAGER").ok().as_deref(),
+        )
+    }
+
+    /// Pick the pager like git: `GIT_PAGER`, then the configured one
+    /// (`pager.<cmd>` or `core.pager`), then `PAGER`, then less
+    pub fn choose(
+        git_pager: Option<&str>,
+        configured: Option<&str>,
+        pager: Option<&str>,
+    ) -> Option<Self> {
+        let command = git_pager
+            .or(configured)
+            .or(pager)
+            .unwrap_or(DEFAULT_PAGER)
+            .trim();
+        if command.is_empty() || command == "cat" {
+            return None;
+        }
+        Some(Self {
+            command: command.to_string(),
+        })
+    }
+
+    /// Get the command line
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Start the pager, reading from a pipe
+    ///
+    /// Like git, sets `LESS=FRX` and `LV=-c` unless the user has their own,
+    /// so less quits by itself on short output and passes colors through.
+    pub fn spawn(&self) -> Result<Child> {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(&self.command).stdin(Stdio::piped());
+        for (key, value) in [("LESS", "FRX"), ("LV", "-c")] {
+            if std::env::var_os(key).is_none() {
+                cmd.env(key, value);
+            }
+        }
+        cmd.spawn()
+            .with_context(|| format!("Failed to start pager: {}", self.command))
+    }
+}
+
+/// Read `pager.<subcommand>` and `core.pager` from git's configuration
+fn read_config(global: &[&str], subcommand: &str) -> (Option<PagerSetting>, Option<String>) {
+    let key = format!("pager.{}", subcommand);
+    let mut pattern = String::from("^(core\\.pager|");
+    for c in key.chars() {
+        if c.is_ascii_punctuation() && c != '-' {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push_str(")$");
+    let mut args = global.to_vec();
+    args.extend(["config", "--get-regexp", &pattern]);
+
+    let (mut setting, mut core_pager) = (None, None);
+    let Ok(result) = CommandExecutor::new("git", &args).run() else {
+        return (setting, core_pager);
+    };
+    // Later values override earlier ones; a key without `=` has no value
+    for line in result.stdout.lines() {
+        let (name, value) = line.split_once(' ').unwrap_or((line, ""));
+        if name == "core.pager" {
+            core_pager = Some(value.to_string());
+        } else if name == key {
+            setting = Some(PagerSetting::parse(value));
+        }
+    }
+    (setting, core_pager)
+}
+
+/// Remove ANSI escape sequences, e.g. colors meant for the pager
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            plain.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: up to BEL or ST
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next() == Some('\\')) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_pager() {
+        let pager = |git, configured, other| {
+            Pager::choose(git, configured, other).map(|p| p.command().to_string())
+        };
+
+        assert_eq!(pager(Some("delta"), Some("bat"), Some("more")), Some("delta".to_string()));
+        assert_eq!(pager(None, Some("bat"), Some("more")), Some("bat".to_string()));
+        assert_eq!(pager(None, None, Some("more")), Some("more".to_string()));
+        assert_eq!(pager(None, None, None), Some("less -R".to_string()));
+        assert_eq!(pager(Some(""), None, Some("more")), None);
+        assert_eq!(pager(None, Some("cat"), None), None);
+
+        assert_eq!(PagerSetting::parse("false"), PagerSetting::Off);
+        assert_eq!(PagerSetting::parse("Yes"), PagerSetting::On);
+        assert_eq!(PagerSetting::parse(""), PagerSetting::On);
+        assert_eq!(
+            PagerSetting::parse("delta --side-by-side"),
+            PagerSetting::Command("delta --side-by-side".to_string())
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[33mcommit 1a2b\x1b[m\n"), "commit 1a2b\n");
+        assert_eq!(strip_ansi("\x1b[1;32m+added\x1b[0m"), "+added");
+        assert_eq!(strip_ansi("\x1b]8;;https://x\x1b\\link\x1b]8;;\x07"), "link");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+}